@@ -1,2 +1,3 @@
 pub mod actor;
 pub mod dispatch;
+pub mod routing;
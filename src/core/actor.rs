@@ -4,30 +4,67 @@ pub mod actor_context;
 pub mod actor_path;
 pub mod actor_ref;
 pub mod actor_ref_provider;
+pub mod actor_selection;
 pub mod actor_system;
 pub mod address;
+pub mod behaviors;
 pub mod child_state;
 pub mod children_refs;
+pub mod coordinated_shutdown;
+pub mod deployer;
 pub mod props;
 pub mod scheduler;
+pub mod supervisor_strategy;
+#[cfg(test)]
+pub(crate) mod test_kit;
 
 use crate::core::actor::actor_context::ActorContext;
 use crate::core::actor::actor_ref::ActorRef;
+use crate::core::actor::supervisor_strategy::SupervisorStrategy;
 use crate::core::dispatch::any_message::AnyMessage;
+use crate::core::dispatch::envelope::Envelope;
 use crate::core::dispatch::message::Message;
 
+use std::any::Any;
 use std::cell::RefCell;
 use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::rc::Rc;
+use std::sync::{Arc, Mutex};
 use thiserror::Error;
 
 pub type ActorResult<A> = Result<A, ActorError>;
 
+/// A message handler installed by `become`; while on the behavior stack it is called instead of `receive`.
+pub type Receive<Msg> = Arc<Mutex<dyn FnMut(ActorContext<Msg>, Msg) -> ActorResult<()> + Send>>;
+
 #[derive(Error, Debug, Clone, PartialEq)]
 pub enum ActorError {
   #[error("Actor failed: {message}")]
   ActorFailed { message: String },
+  #[error("Actor killed")]
+  ActorKilled,
+  #[error("Actor panicked: {payload}")]
+  ActorPanicked {
+    payload: String,
+    current_message: Option<Box<Envelope>>,
+  },
+}
+
+impl ActorError {
+  pub fn of_panicked(payload: Box<dyn Any + Send>, current_message: Option<Envelope>) -> Self {
+    let payload = if let Some(s) = payload.downcast_ref::<&str>() {
+      s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+      s.clone()
+    } else {
+      "unknown panic payload".to_string()
+    };
+    ActorError::ActorPanicked {
+      payload,
+      current_message: current_message.map(Box::new),
+    }
+  }
 }
 
 pub trait ActorBehavior<Msg: Message>: Debug {
@@ -46,6 +83,14 @@ pub trait ActorBehavior<Msg: Message>: Debug {
     Ok(())
   }
 
+  fn around_post_restart(&mut self, ctx: ActorContext<Msg>, reason: ActorError) -> ActorResult<()> {
+    self.post_restart(ctx, reason)
+  }
+
+  fn post_restart(&mut self, ctx: ActorContext<Msg>, _reason: ActorError) -> ActorResult<()> {
+    self.pre_start(ctx)
+  }
+
   fn around_pre_start(&mut self, ctx: ActorContext<Msg>) -> ActorResult<()> {
     self.pre_start(ctx)
   }
@@ -90,6 +135,19 @@ pub trait ActorBehavior<Msg: Message>: Debug {
     log::info!("default child_terminated");
     Ok(())
   }
+
+  fn around_terminated(&mut self, ctx: ActorContext<Msg>, actor: ActorRef<AnyMessage>) -> ActorResult<()> {
+    self.terminated(ctx, actor)
+  }
+
+  fn terminated(&mut self, _ctx: ActorContext<Msg>, _actor: ActorRef<AnyMessage>) -> ActorResult<()> {
+    log::info!("default terminated");
+    Ok(())
+  }
+
+  fn supervisor_strategy(&self) -> SupervisorStrategy {
+    SupervisorStrategy::default()
+  }
 }
 
 #[derive(Debug, Clone)]
@@ -108,21 +166,70 @@ impl<Msg: Message> ActorBehavior<Msg> for MockActorMutable<Msg> {
 }
 
 #[derive(Debug, Clone)]
-pub struct AnyMessageActorWrapper<Msg: Message, Actor: ActorBehavior<Msg>> {
-  inner_actor: Actor,
+pub struct AnyMessageActorWrapper<Msg: Message> {
+  inner_actor: Rc<RefCell<dyn ActorBehavior<Msg>>>,
 }
 
-impl<Msg: Message, Actor: ActorBehavior<Msg>> AnyMessageActorWrapper<Msg, Actor> {
-  pub fn new(actor: Actor) -> Self {
+impl<Msg: Message> AnyMessageActorWrapper<Msg> {
+  pub fn new(actor: Rc<RefCell<dyn ActorBehavior<Msg>>>) -> Self {
     Self { inner_actor: actor }
   }
 }
 
-impl<Msg: Message, Actor: ActorBehavior<Msg>> ActorBehavior<AnyMessage> for AnyMessageActorWrapper<Msg, Actor> {
+impl<Msg: Message> ActorBehavior<AnyMessage> for AnyMessageActorWrapper<Msg> {
   fn receive(&mut self, ctx: ActorContext<AnyMessage>, msg: AnyMessage) -> ActorResult<()> {
     let typed_msg = msg.take::<Msg>().unwrap();
     let typed_ctx = ctx.to_typed(true);
-    self.inner_actor.around_receive(typed_ctx, typed_msg)
+    let mut actor = self.inner_actor.borrow_mut();
+    actor.around_receive(typed_ctx, typed_msg)
+  }
+
+  fn pre_restart(
+    &mut self,
+    ctx: ActorContext<AnyMessage>,
+    reason: ActorError,
+    msg: Option<AnyMessage>,
+  ) -> ActorResult<()> {
+    let typed_msg = msg.and_then(|m| m.take::<Msg>().ok());
+    let typed_ctx = ctx.to_typed(true);
+    let mut actor = self.inner_actor.borrow_mut();
+    actor.around_pre_restart(typed_ctx, reason, typed_msg)
+  }
+
+  fn post_restart(&mut self, ctx: ActorContext<AnyMessage>, reason: ActorError) -> ActorResult<()> {
+    let typed_ctx = ctx.to_typed(true);
+    let mut actor = self.inner_actor.borrow_mut();
+    actor.around_post_restart(typed_ctx, reason)
+  }
+
+  fn pre_start(&mut self, ctx: ActorContext<AnyMessage>) -> ActorResult<()> {
+    let typed_ctx = ctx.to_typed(true);
+    let mut actor = self.inner_actor.borrow_mut();
+    actor.around_pre_start(typed_ctx)
+  }
+
+  fn pre_suspend(&mut self, ctx: ActorContext<AnyMessage>) -> ActorResult<()> {
+    let typed_ctx = ctx.to_typed(true);
+    let mut actor = self.inner_actor.borrow_mut();
+    actor.around_pre_suspend(typed_ctx)
+  }
+
+  fn post_resume(&mut self, ctx: ActorContext<AnyMessage>, caused_by_failure: Option<ActorError>) -> ActorResult<()> {
+    let typed_ctx = ctx.to_typed(true);
+    let mut actor = self.inner_actor.borrow_mut();
+    actor.around_post_resume(typed_ctx, caused_by_failure)
+  }
+
+  fn post_stop(&mut self, ctx: ActorContext<AnyMessage>) -> ActorResult<()> {
+    let typed_ctx = ctx.to_typed(true);
+    let mut actor = self.inner_actor.borrow_mut();
+    actor.around_post_stop(typed_ctx)
+  }
+
+  fn around_child_terminated(&mut self, ctx: ActorContext<AnyMessage>, child: ActorRef<AnyMessage>) -> ActorResult<()> {
+    let typed_ctx = ctx.to_typed(true);
+    let mut actor = self.inner_actor.borrow_mut();
+    actor.around_child_terminated(typed_ctx, child)
   }
 
   fn child_terminated(&mut self, /* _ctx: ActorContext<Msg>, */ child: ActorRef<AnyMessage>) -> ActorResult<()> {
@@ -130,4 +237,15 @@ impl<Msg: Message, Actor: ActorBehavior<Msg>> ActorBehavior<AnyMessage> for AnyM
     let mut actor = self.inner_actor.borrow_mut();
     actor.child_terminated(child)
   }
+
+  fn terminated(&mut self, ctx: ActorContext<AnyMessage>, actor: ActorRef<AnyMessage>) -> ActorResult<()> {
+    let typed_ctx = ctx.to_typed(true);
+    let mut inner_actor = self.inner_actor.borrow_mut();
+    inner_actor.around_terminated(typed_ctx, actor)
+  }
+
+  fn supervisor_strategy(&self) -> SupervisorStrategy {
+    let actor = self.inner_actor.borrow();
+    actor.supervisor_strategy()
+  }
 }
@@ -1,24 +1,35 @@
+use std::any::{Any, TypeId};
 use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::fmt::{Debug, Formatter};
+use std::future::Future;
 
+use std::panic::{self, AssertUnwindSafe};
 use std::rc::Rc;
 use std::sync::atomic::AtomicBool;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
 
 use rand::{thread_rng, RngCore};
 use tokio::runtime;
 
+use crate::core::actor::actor_cell::fault_info::FailedInfo;
+use crate::core::actor::actor_cell::message_adapter::MessageAdapter;
 use crate::core::actor::actor_cell_with_ref::ActorCellWithRef;
 use crate::core::actor::actor_context::ActorContext;
-use crate::core::actor::actor_path::{ActorPath, ActorPathBehavior};
-use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior, AnyActorRef, AnyActorRefBehavior};
+use crate::core::actor::actor_path::ActorPath;
+use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior, AnyActorRef};
+use crate::core::actor::actor_selection::{ActorIdentity, Identify};
 
-use crate::core::actor::children_refs::ChildrenRefs;
+use crate::core::actor::children_refs::{ActorCreationError, ChildrenRefs};
 use crate::core::actor::props::{AnyProps, Props};
-use crate::core::actor::{ActorBehavior, ActorError, AnyMessageActorWrapper};
+use crate::core::actor::scheduler::Cancellable;
+use crate::core::actor::supervisor_strategy::SupervisorStrategy;
+use crate::core::actor::{ActorBehavior, ActorError, ActorResult, AnyMessageActorWrapper, Receive};
 use crate::core::dispatch::any_message::AnyMessage;
 use crate::core::dispatch::dispatcher::{Dispatcher, DispatcherBehavior};
 use crate::core::dispatch::envelope::Envelope;
+use crate::core::dispatch::mailbox::dead_letter::DeadLetter;
 use crate::core::dispatch::mailbox::dead_letter_mailbox::DeadLetterMailbox;
 use crate::core::dispatch::mailbox::mailbox::{Mailbox, MailboxSender};
 use crate::core::dispatch::mailbox::mailbox_type::MailboxType;
@@ -26,11 +37,22 @@ use crate::core::dispatch::message::Message;
 use crate::core::dispatch::system_message::system_message::SystemMessage;
 use crate::core::dispatch::system_message::system_message_entry::SystemMessageEntry;
 use crate::core::dispatch::system_message::SystemMessageQueueWriterBehavior;
+use crate::core::routing::RouterProps;
 
 use crate::infrastructure::logging_mutex::LoggingMutex;
 
 use crate::mutex_lock_with_log;
-use tokio::sync::oneshot;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+pub mod behavior_stack;
+pub mod death_watch;
+pub mod fault_info;
+pub mod message_adapter;
+pub mod pipe_to_self;
+pub mod receive_timeout;
+pub mod stash;
+pub mod tasks;
 
 pub const UNDEFINED_UID: u32 = 0;
 
@@ -59,7 +81,39 @@ pub fn split_name_and_uid(name: &str) -> (&str, u32) {
 #[derive(Debug, Clone, PartialEq)]
 pub enum AutoReceivedMessage {
   PoisonPill,
-  Terminated(ActorRef<AnyMessage>),
+  Kill,
+  Adapt { type_id: TypeId, message: AnyMessage },
+  /// A receive timeout, dropped unless `generation` is still the current one.
+  ReceiveTimeout { generation: u64 },
+}
+
+/// The typed cell behind an `ActorCell<AnyMessage>` view.
+///
+/// Views created by `to_any` forward processing here, so that the actor and its state exist only once.
+/// Views are handed to other threads, so the typed cell is shared through an `Arc`.
+trait TypedActorCell: Send + Sync {
+  fn exists_actor(&self) -> bool;
+  fn invoke_any(&self, self_ref: ActorRef<AnyMessage>, msg: &Envelope);
+  fn system_invoke_any(&self, self_ref: ActorRef<AnyMessage>, msg: &SystemMessage);
+  fn as_any(&self) -> &dyn Any;
+}
+
+impl<Msg: Message> TypedActorCell for ActorCell<Msg> {
+  fn exists_actor(&self) -> bool {
+    ActorCell::exists_actor(self)
+  }
+
+  fn invoke_any(&self, self_ref: ActorRef<AnyMessage>, msg: &Envelope) {
+    self.clone().invoke(self_ref.to_typed(false), msg);
+  }
+
+  fn system_invoke_any(&self, self_ref: ActorRef<AnyMessage>, msg: &SystemMessage) {
+    self.clone().system_invoke(self_ref.to_typed(false), msg);
+  }
+
+  fn as_any(&self) -> &dyn Any {
+    self
+  }
 }
 
 #[derive(Clone)]
@@ -72,8 +126,20 @@ struct ActorCellInner<Msg: Message> {
   mailbox_sender: Option<MailboxSender<Msg>>,
   props: Rc<dyn Props<Msg>>,
   actor: Option<Rc<RefCell<dyn ActorBehavior<Msg>>>>,
+  behavior_stack: Vec<Receive<Msg>>,
   children: ChildrenRefs,
-  current_message: Rc<RefCell<Option<Envelope>>>,
+  current_message: Arc<Mutex<Option<Envelope>>>,
+  stash: Arc<Mutex<VecDeque<Envelope>>>,
+  failed: FailedInfo,
+  watching: Vec<(ActorRef<AnyMessage>, Option<Msg>)>,
+  watched_by: Vec<ActorRef<AnyMessage>>,
+  receive_timeout: Option<(Duration, Msg)>,
+  receive_timeout_task: Option<Cancellable>,
+  receive_timeout_generation: u64,
+  message_adapters: Vec<(TypeId, MessageAdapter<Msg>)>,
+  tasks: Arc<Mutex<Vec<JoinHandle<()>>>>,
+  typed_cell: Option<Arc<dyn TypedActorCell>>,
+  terminated: Arc<AtomicBool>,
 }
 
 impl<Msg: Message> Debug for ActorCellInner<Msg> {
@@ -87,8 +153,16 @@ impl<Msg: Message> Debug for ActorCellInner<Msg> {
       .field("mailbox_sender", &self.mailbox_sender)
       .field("props", &self.props)
       .field("actor", &"Fn")
+      .field("behavior_stack", &self.behavior_stack.len())
       .field("children", &self.children)
       .field("current_message", &self.current_message)
+      .field("stash", &self.stash)
+      .field("failed", &self.failed)
+      .field("watching", &self.watching)
+      .field("watched_by", &self.watched_by)
+      .field("receive_timeout", &self.receive_timeout)
+      .field("message_adapters", &self.message_adapters.len())
+      .field("tasks", &self.tasks.lock().unwrap().len())
       .finish()
   }
 }
@@ -98,8 +172,8 @@ pub struct ActorCell<Msg: Message> {
   initialized: Arc<AtomicBool>,
   inner: Arc<LoggingMutex<ActorCellInner<Msg>>>,
   path: ActorPath,
-  terminated_rx: Arc<Mutex<Option<oneshot::Receiver<()>>>>,
-  tx: Arc<Mutex<Option<oneshot::Sender<()>>>>,
+  terminated_tx: Arc<watch::Sender<bool>>,
+  terminated_rx: Arc<watch::Receiver<bool>>,
 }
 
 unsafe impl<Msg: Message> Send for ActorCell<Msg> {}
@@ -121,10 +195,10 @@ impl<Msg: Message> ActorCell<Msg> {
     props: Rc<dyn Props<Msg>>,
     parent_ref: Option<AnyActorRef>,
   ) -> Self {
-    let (tx, rx) = oneshot::channel();
+    let (terminated_tx, terminated_rx) = watch::channel(false);
     ActorCell {
-      terminated_rx: Arc::new(Mutex::new(Some(rx))),
-      tx: Arc::new(Mutex::new(Some(tx))),
+      terminated_tx: Arc::new(terminated_tx),
+      terminated_rx: Arc::new(terminated_rx),
       path: path.clone(),
       initialized: Arc::new(AtomicBool::new(false)),
       inner: Arc::new(LoggingMutex::new(
@@ -138,8 +212,20 @@ impl<Msg: Message> ActorCell<Msg> {
           dead_letter_mailbox: None,
           props,
           actor: None,
+          behavior_stack: Vec::new(),
           children: ChildrenRefs::new(),
-          current_message: Rc::new(RefCell::new(None)),
+          current_message: Arc::new(Mutex::new(None)),
+          stash: Arc::new(Mutex::new(VecDeque::new())),
+          failed: FailedInfo::NoFailedInfo,
+          watching: Vec::new(),
+          watched_by: Vec::new(),
+          receive_timeout: None,
+          receive_timeout_task: None,
+          receive_timeout_generation: 0,
+          message_adapters: Vec::new(),
+          tasks: Arc::new(Mutex::new(Vec::new())),
+          typed_cell: None,
+          terminated: Arc::new(AtomicBool::new(false)),
         },
       )),
     }
@@ -150,6 +236,11 @@ impl<Msg: Message> ActorCell<Msg> {
     inner.children.clone()
   }
 
+  pub fn parent_ref(&self) -> Option<ActorRef<AnyMessage>> {
+    let inner = mutex_lock_with_log!(self.inner, "parent_ref");
+    inner.parent_ref.clone()
+  }
+
   pub fn initialize(
     &mut self,
     self_ref: ActorRef<Msg>,
@@ -197,30 +288,18 @@ impl<Msg: Message> ActorCell<Msg> {
     inner.dead_letter_mailbox.as_ref().unwrap().clone()
   }
 
-  fn check_actor<U: Message>(
-    validate_actor: bool,
-    actor: Option<Rc<RefCell<dyn ActorBehavior<U>>>>,
-  ) -> Option<Rc<RefCell<dyn ActorBehavior<U>>>> {
-    match &actor {
-      None => {
-        if validate_actor {
-          panic!("ActorCell not initialized")
-        } else {
-          actor
-        }
-      }
-      Some(_) => actor,
-    }
-  }
-
   pub fn to_any(self, validate_actor: bool) -> ActorCell<AnyMessage> {
     if validate_actor && !self.initialized.load(std::sync::atomic::Ordering::Relaxed) {
       panic!("ActorCell not initialized");
     }
     let inner = mutex_lock_with_log!(self.inner, "to_any");
+    let typed_cell: Arc<dyn TypedActorCell> = match &inner.typed_cell {
+      Some(typed_cell) => typed_cell.clone(),
+      None => Arc::new(self.clone()),
+    };
     ActorCell {
+      terminated_tx: self.terminated_tx.clone(),
       terminated_rx: self.terminated_rx.clone(),
-      tx: self.tx.clone(),
       path: inner.path.clone(),
       initialized: self.initialized.clone(),
       inner: Arc::new(LoggingMutex::new(
@@ -237,26 +316,84 @@ impl<Msg: Message> ActorCell<Msg> {
             None => None,
             Some(actor) => Some(Rc::new(RefCell::new(AnyMessageActorWrapper::new(actor.clone())))),
           },
+          behavior_stack: Vec::new(),
           children: inner.children.clone(),
           current_message: inner.current_message.clone(),
+          stash: inner.stash.clone(),
+          failed: inner.failed.clone(),
+          watching: Vec::new(),
+          watched_by: Vec::new(),
+          receive_timeout: None,
+          receive_timeout_task: None,
+          receive_timeout_generation: 0,
+          message_adapters: Vec::new(),
+          tasks: inner.tasks.clone(),
+          typed_cell: Some(typed_cell),
+          terminated: inner.terminated.clone(),
         },
       )),
     }
   }
 
+  pub fn is_terminated(&self) -> bool {
+    let inner = mutex_lock_with_log!(self.inner, "is_terminated");
+    inner.terminated.load(std::sync::atomic::Ordering::Relaxed)
+  }
+
+  fn actor(&self) -> Option<Rc<RefCell<dyn ActorBehavior<Msg>>>> {
+    let inner = mutex_lock_with_log!(self.inner, "actor");
+    inner.actor.clone()
+  }
+
+  fn typed_cell(&self) -> Option<Arc<dyn TypedActorCell>> {
+    let inner = mutex_lock_with_log!(self.inner, "typed_cell");
+    inner.typed_cell.clone()
+  }
+
   pub fn send_message(&mut self, self_ref: ActorRef<Msg>, msg: Msg) {
+    self.send_envelope(self_ref, Envelope::new(msg));
+  }
+
+  pub fn send_message_with_sender(&mut self, self_ref: ActorRef<Msg>, msg: Msg, sender: ActorRef<AnyMessage>) {
+    self.send_envelope(self_ref, Envelope::new_with_sender(msg, sender));
+  }
+
+  fn send_envelope(&mut self, self_ref: ActorRef<Msg>, envelope: Envelope) {
     if !self.initialized.load(std::sync::atomic::Ordering::Relaxed) {
       panic!("ActorCell not initialized");
     }
     let mut dispatcher = {
-      let inner = mutex_lock_with_log!(self.inner, "send_message");
+      let inner = mutex_lock_with_log!(self.inner, "send_envelope");
       inner.dispatcher.clone()
     };
     let ctx = ActorCellWithRef::new(self.clone(), self_ref);
-    let envelope = Envelope::new(msg);
     dispatcher.dispatch(ctx, envelope);
   }
 
+  /// Returns the sender of the message being processed, or `ActorRef::NoSender` if it has none.
+  pub fn sender(&self) -> ActorRef<AnyMessage> {
+    let inner = mutex_lock_with_log!(self.inner, "sender");
+    let sender = inner
+      .current_message
+      .lock()
+      .unwrap()
+      .as_ref()
+      .and_then(|envelope| envelope.sender())
+      .unwrap_or(ActorRef::NoSender);
+    sender
+  }
+
+  pub(crate) fn dispatcher(&self) -> Dispatcher {
+    let inner = mutex_lock_with_log!(self.inner, "dispatcher");
+    inner.dispatcher.clone()
+  }
+
+  fn reply_identity(&self, self_ref: ActorRef<Msg>, identify: Identify) {
+    let self_ref = self_ref.to_any(false);
+    let identity = ActorIdentity::new(identify.message_id(), Some(self_ref.clone()));
+    self.sender().tell_any_with_sender(AnyMessage::new(identity), self_ref);
+  }
+
   pub fn send_system_message(&mut self, self_ref: ActorRef<Msg>, msg: &mut SystemMessageEntry) {
     if !self.initialized.load(std::sync::atomic::Ordering::Relaxed) {
       panic!("ActorCell not initialized");
@@ -274,15 +411,45 @@ impl<Msg: Message> ActorCell<Msg> {
     self_ref: ActorRef<Msg>,
     props: Rc<dyn Props<U>>,
     name: &str,
-  ) -> ActorRef<U> {
+  ) -> Result<ActorRef<U>, ActorCreationError> {
     if !self.initialized.load(std::sync::atomic::Ordering::Relaxed) {
       panic!("ActorCell not initialized");
     }
-    let actor_path = ActorPath::of_child(self_ref.path(), name, 0);
-    let dispatcher = {
+    let actor_path = ActorPath::of_child(self_ref.path(), name, new_uid());
+    let parent_dispatcher = {
       let inner = mutex_lock_with_log!(self.inner, "new_child_actor");
       inner.dispatcher.clone()
     };
+    // The deployment configured for the path overrides what the props specify.
+    let deploy = parent_dispatcher.deployer().lookup(&actor_path);
+    let dispatcher_name = deploy
+      .as_ref()
+      .and_then(|deploy| deploy.dispatcher.clone())
+      .or_else(|| props.dispatcher());
+    let dispatcher = match dispatcher_name {
+      Some(name) => parent_dispatcher
+        .lookup(&name)
+        .ok_or_else(|| ActorCreationError::UnknownDispatcher {
+          dispatcher: name,
+          path: actor_path.to_string(),
+        })?,
+      None => parent_dispatcher,
+    };
+    let mailbox_type = deploy
+      .as_ref()
+      .and_then(|deploy| deploy.mailbox_type.clone())
+      .or_else(|| props.mailbox_type())
+      .unwrap_or_else(|| dispatcher.mailboxes().lock().unwrap().default_mailbox_type());
+    // A routee is never turned into a router, even if a deployment with a wildcard matches its path.
+    let router_config = deploy
+      .as_ref()
+      .filter(|_| !props.is_routee())
+      .and_then(|deploy| deploy.router_config.clone())
+      .or_else(|| props.router_config());
+    let props: Rc<dyn Props<U>> = match router_config {
+      Some(router_config) => Rc::new(RouterProps::new_with_deploy(router_config, props, deploy)),
+      None => props,
+    };
     let mut child_actor_cell = ActorCell::new(
       dispatcher.clone(),
       actor_path.clone(),
@@ -290,41 +457,58 @@ impl<Msg: Message> ActorCell<Msg> {
       Some(self_ref.to_any(true)),
     );
     let actor_ref = ActorRef::of_local(child_actor_cell.clone(), actor_path);
-    child_actor_cell.initialize(
-      actor_ref.clone(),
-      MailboxType::Unbounded,
-      self.dead_letter_mailbox(),
-      true,
-    );
-    actor_ref
+    child_actor_cell.initialize(actor_ref.clone(), mailbox_type, self.dead_letter_mailbox(), true);
+    Ok(actor_ref)
   }
 
+  /// Panics if the child cannot be created; see `try_actor_of`.
   pub fn actor_of<U: Message>(&mut self, self_ref: ActorRef<Msg>, props: Rc<dyn Props<U>>) -> ActorRef<U> {
+    self
+      .try_actor_of(self_ref, props)
+      .unwrap_or_else(|error| panic!("{}", error))
+  }
+
+  pub fn try_actor_of<U: Message>(
+    &mut self,
+    self_ref: ActorRef<Msg>,
+    props: Rc<dyn Props<U>>,
+  ) -> Result<ActorRef<U>, ActorCreationError> {
     if !self.initialized.load(std::sync::atomic::Ordering::Relaxed) {
       panic!("ActorCell not initialized");
     }
     let mut children = {
-      let inner = mutex_lock_with_log!(self.inner, "actor_of");
+      let inner = mutex_lock_with_log!(self.inner, "try_actor_of");
       inner.children.clone()
     };
-    children.actor_of(self.clone().to_any(true), self_ref.to_any(true), props)
+    children.try_actor_of(self.clone().to_any(true), self_ref.to_any(true), props)
   }
 
+  /// Panics if the child cannot be created; see `try_actor_with_name_of`.
   pub fn actor_with_name_of<U: Message>(
     &mut self,
     self_ref: ActorRef<Msg>,
     props: Rc<dyn Props<U>>,
     name: &str,
   ) -> ActorRef<U> {
+    self
+      .try_actor_with_name_of(self_ref, props, name)
+      .unwrap_or_else(|error| panic!("{}", error))
+  }
+
+  pub fn try_actor_with_name_of<U: Message>(
+    &mut self,
+    self_ref: ActorRef<Msg>,
+    props: Rc<dyn Props<U>>,
+    name: &str,
+  ) -> Result<ActorRef<U>, ActorCreationError> {
     if !self.initialized.load(std::sync::atomic::Ordering::Relaxed) {
       panic!("ActorCell not initialized");
     }
     let mut children = {
-      let inner = mutex_lock_with_log!(self.inner, "actor_with_name_of");
+      let inner = mutex_lock_with_log!(self.inner, "try_actor_with_name_of");
       inner.children.clone()
     };
-    let result = children.actor_with_name_of(self.clone().to_any(true), self_ref.to_any(true), props, name);
-    result
+    children.actor_with_name_of(self.clone().to_any(true), self_ref.to_any(true), props, name)
   }
 
   pub(crate) fn start(&mut self, self_ref: ActorRef<Msg>) {
@@ -343,6 +527,9 @@ impl<Msg: Message> ActorCell<Msg> {
   }
 
   fn exists_actor(&self) -> bool {
+    if let Some(typed_cell) = self.typed_cell() {
+      return typed_cell.exists_actor();
+    }
     let inner = mutex_lock_with_log!(self.inner, "exsits_actor");
     inner.actor.is_some()
   }
@@ -398,6 +585,11 @@ impl ActorCell<AnyMessage> {
     if validate_actor && !self.initialized.load(std::sync::atomic::Ordering::Relaxed) {
       panic!("ActorCell not initialized");
     }
+    if let Some(typed_cell) = self.typed_cell() {
+      if let Some(actor_cell) = typed_cell.as_any().downcast_ref::<ActorCell<Msg>>() {
+        return actor_cell.clone();
+      }
+    }
     let inner_actor = {
       let inner = mutex_lock_with_log!(self.inner, "to_typed");
       if let Some(actor) = &inner.actor {
@@ -422,8 +614,8 @@ impl ActorCell<AnyMessage> {
     };
     let inner = mutex_lock_with_log!(self.inner, "to_typed");
     ActorCell {
+      terminated_tx: self.terminated_tx,
       terminated_rx: self.terminated_rx,
-      tx: self.tx,
       path: inner.path.clone(),
       initialized: self.initialized.clone(),
       inner: Arc::new(LoggingMutex::new(
@@ -437,8 +629,20 @@ impl ActorCell<AnyMessage> {
           mailbox_sender: inner.mailbox_sender.clone().map(MailboxSender::to_typed),
           props: inner_underlying,
           actor: inner_actor,
+          behavior_stack: Vec::new(),
           children: inner.children.clone(),
           current_message: inner.current_message.clone(),
+          stash: inner.stash.clone(),
+          failed: inner.failed.clone(),
+          watching: Vec::new(),
+          watched_by: Vec::new(),
+          receive_timeout: None,
+          receive_timeout_task: None,
+          receive_timeout_generation: 0,
+          message_adapters: Vec::new(),
+          tasks: inner.tasks.clone(),
+          typed_cell: None,
+          terminated: inner.terminated.clone(),
         },
       )),
     }
@@ -475,6 +679,10 @@ impl<Msg: Message> ActorCellBehavior<Msg> for ActorCell<Msg> {
         msg.clone().typed_message::<Msg>().unwrap()
       );
     }
+    if let Some(typed_cell) = self.typed_cell() {
+      typed_cell.invoke_any(self_ref.to_any(false), msg);
+      return;
+    }
     // if !self.exists_actor() {
     //   panic!(
     //     "ActorCell not exists actor: path = {}, msg = {:?}",
@@ -484,48 +692,50 @@ impl<Msg: Message> ActorCellBehavior<Msg> for ActorCell<Msg> {
     // }
     {
       let inner = mutex_lock_with_log!(self.inner, "invoke");
-      let mut current_message = inner.current_message.borrow_mut();
+      let mut current_message = inner.current_message.lock().unwrap();
       *current_message = Some(msg.clone());
     }
 
     let auto_received_message = msg.clone().typed_message::<AnyMessage>();
     match auto_received_message {
       Ok(msg) => match msg.take::<AutoReceivedMessage>() {
-        Ok(AutoReceivedMessage::Terminated(ar)) => {
-          {
-            let _ctx = ActorContext::new(self.clone(), self_ref.clone());
-            let mut inner = mutex_lock_with_log!(self.inner, "invoke").clone();
-            let mut actor = inner.actor.as_mut().unwrap().borrow_mut();
-            actor.around_child_terminated(_ctx, ar.clone()).unwrap();
+        Ok(AutoReceivedMessage::PoisonPill) => {
+          self.stop(self_ref.clone());
+        }
+        Ok(AutoReceivedMessage::Kill) => {
+          self.handle_invoke_failure(self_ref.clone(), None, ActorError::ActorKilled);
+        }
+        Ok(AutoReceivedMessage::Adapt { type_id, message }) => {
+          if let Some(msg) = self.adapt(type_id, message) {
+            self.receive_message(self_ref.clone(), msg);
           }
-          let is_empty = {
-            let mut inner = mutex_lock_with_log!(self.inner, "invoke");
-            inner.children.un_reserve_child(ar.path().name());
-            inner.children.is_empty()
-          };
-          if is_empty {
-            self.tell_terminated_to_parent(self_ref);
+        }
+        Ok(AutoReceivedMessage::ReceiveTimeout { generation }) => {
+          if let Some(msg) = self.current_receive_timeout_message(generation) {
+            self.receive_message(self_ref.clone(), msg);
           }
         }
-        Ok(msg) => {
-          log::info!("auto_received_message - {:?}", msg);
+        Err(_) => {
+          if let Ok(identify) = msg.take::<Identify>() {
+            self.reply_identity(self_ref.clone(), identify);
+          } else if let Ok(msg) = msg.take::<Msg>() {
+            self.receive_message(self_ref.clone(), msg);
+          } else {
+            log::warn!("invoke: actor({}) cannot handle {:?}", self_ref.path(), msg);
+            let dead_letter = DeadLetter::new(msg, self.sender(), self_ref.to_any(false));
+            self.dead_letter_mailbox().dead_letters().tell(AnyMessage::new(dead_letter));
+          }
         }
-        _ => {}
       },
       Err(_) => {
-        let ctx = ActorContext::new(self.clone(), self_ref.clone());
         let msg = msg.clone().typed_message::<Msg>().unwrap();
-        log::info!("received_message - {:?}", msg);
-        let mut inner = mutex_lock_with_log!(self.inner, "invoke").clone();
-        let mut actor = inner.actor.as_mut().unwrap().borrow_mut();
-        actor.around_receive(ctx, msg).unwrap();
+        self.receive_message(self_ref.clone(), msg);
       }
     }
 
-    {
-      let inner = mutex_lock_with_log!(self.inner, "invoke");
-      let mut cm = inner.current_message.borrow_mut();
-      *cm = None;
+    // the current message is kept for pre_restart while the failure is being handled
+    if !self.is_failed() {
+      self.clear_current_message();
     }
   }
 
@@ -533,50 +743,37 @@ impl<Msg: Message> ActorCellBehavior<Msg> for ActorCell<Msg> {
     if !self.initialized.load(std::sync::atomic::Ordering::Relaxed) {
       panic!("ActorCell not initialized");
     }
+    if let Some(typed_cell) = self.typed_cell() {
+      typed_cell.system_invoke_any(self_ref.to_any(false), msg);
+      return;
+    }
     match msg {
       SystemMessage::Create { failure: _ } => {
-        let mut actor_rc = {
-          let mut inner = mutex_lock_with_log!(self.inner, "system_invoke");
-          inner.actor = Some(inner.props.new_actor());
-          inner.actor.clone()
-        };
-        let ctx = ActorContext::new(self.clone(), self_ref);
-        let mut actor = actor_rc.as_mut().unwrap().borrow_mut();
-        actor.around_pre_start(ctx).unwrap();
+        self.create(self_ref);
+      }
+      SystemMessage::Recreate { cause } => {
+        self.fault_recreate(self_ref, cause.clone());
+      }
+      SystemMessage::Suspend => {
+        self.fault_suspend(self_ref);
+      }
+      SystemMessage::Resume { caused_by_failure } => {
+        self.fault_resume(self_ref, caused_by_failure.clone());
       }
       SystemMessage::Terminate => {
-        let is_empty;
-        {
-          let inner = mutex_lock_with_log!(self.inner, "system_invoke");
-          is_empty = inner.children.is_empty();
-          if inner.children.non_empty() {
-            inner.children.stop_all_children();
-          }
-        }
-        {
-          let mut inner = mutex_lock_with_log!(self.inner, "system_invoke");
-          match inner.actor {
-            Some(ref mut actor) => {
-              let ctx = ActorContext::new(self.clone(), self_ref.clone());
-              let mut actor_ref_mut = actor.borrow_mut();
-              actor_ref_mut.around_post_stop(ctx).unwrap();
-            }
-            None => {
-              log::warn!("system_invoke: actor({}) is None", self_ref.path());
-            }
-          }
-        }
-        if is_empty {
-          self.tell_terminated_to_parent(self_ref);
-        }
-        {
-          let mut inner = mutex_lock_with_log!(self.inner, "system_invoke");
-          inner.children.clear();
-          let parent_ref = inner.parent_ref.take();
-          drop(parent_ref);
-          let actor = inner.actor.take();
-          drop(actor);
-        }
+        self.terminate(self_ref);
+      }
+      SystemMessage::Failed { child, error, uid } => {
+        self.handle_failed(self_ref, child.clone(), error.clone(), *uid);
+      }
+      SystemMessage::Watch { watchee, watcher } => {
+        self.add_watcher(self_ref, watchee.clone(), watcher.clone());
+      }
+      SystemMessage::Unwatch { watcher, .. } => {
+        self.remove_watcher(watcher.clone());
+      }
+      SystemMessage::DeathWatchNotification { actor, .. } => {
+        self.watched_actor_terminated(self_ref, actor.clone());
       }
       _ => {}
     }
@@ -586,34 +783,132 @@ impl<Msg: Message> ActorCellBehavior<Msg> for ActorCell<Msg> {
 impl<Msg: Message> ActorCell<Msg> {
   pub fn when_terminate(&self) {
     let runner = runtime::Builder::new_current_thread().enable_all().build().unwrap();
-    let mut rx_g = self.terminated_rx.lock().unwrap();
-    let rx = rx_g.take().unwrap();
-    runner.block_on(async move {
-      match rx.await {
-        Ok(()) => {
-          log::info!("when_terminate: terminated");
-        }
-        Err(error) => {
-          log::error!("when_terminate: error = {:?}", error);
+    runner.block_on(self.terminated());
+    log::info!("when_terminate: terminated");
+  }
+
+  /// Returns a future that resolves once this actor has terminated; every call waits on its own subscription.
+  pub(crate) fn terminated(&self) -> impl Future<Output = ()> {
+    let mut terminated_rx = self.terminated_rx.as_ref().clone();
+    async move {
+      while !*terminated_rx.borrow() {
+        if terminated_rx.changed().await.is_err() {
+          break;
         }
       }
+    }
+  }
+
+  fn notify_terminated(&self) {
+    let _ = self.terminated_tx.send(true);
+  }
+
+  fn receive_message(&mut self, self_ref: ActorRef<Msg>, msg: Msg) {
+    let ctx = ActorContext::new(self.clone(), self_ref.clone());
+    log::info!("received_message - {:?}", msg);
+    let (actor_opt, behavior_opt) = {
+      let inner = mutex_lock_with_log!(self.inner, "receive_message");
+      (inner.actor.clone(), inner.behavior_stack.last().cloned())
+    };
+    let result = match behavior_opt {
+      Some(behavior) => self.catch_panic(|| {
+        let mut behavior = behavior.lock().unwrap_or_else(PoisonError::into_inner);
+        behavior(ctx, msg)
+      }),
+      None => {
+        let actor = actor_opt.unwrap();
+        self.catch_panic(|| actor.borrow_mut().around_receive(ctx, msg))
+      }
+    };
+    // the idle timer restarts after every message, also one that failed
+    self.reschedule_receive_timeout(self_ref.clone());
+    if let Err(error) = result {
+      self.handle_invoke_failure(self_ref, None, error);
+    }
+  }
+
+  fn create(&mut self, self_ref: ActorRef<Msg>) {
+    let actor_rc = {
+      let mut inner = mutex_lock_with_log!(self.inner, "create");
+      let actor_rc = inner.props.new_actor();
+      inner.actor = Some(actor_rc.clone());
+      actor_rc
+    };
+    let ctx = ActorContext::new(self.clone(), self_ref.clone());
+    let result = self.catch_panic(|| actor_rc.borrow_mut().around_pre_start(ctx));
+    if let Err(error) = result {
+      self.clear_current_message();
+      self.set_failed_fatally();
+      {
+        let mut inner = mutex_lock_with_log!(self.inner, "create");
+        inner.actor = None;
+      }
+      self.handle_invoke_failure(self_ref, None, error);
+    }
+  }
+
+  /// Runs an actor callback, turning a panic into `ActorError::ActorPanicked` so that it takes the normal failure path.
+  fn catch_panic<F>(&self, f: F) -> ActorResult<()>
+  where
+    F: FnOnce() -> ActorResult<()>, {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+      Ok(result) => result,
+      Err(payload) => {
+        let current_message = {
+          let inner = mutex_lock_with_log!(self.inner, "catch_panic");
+          let current_message = inner.current_message.lock().unwrap().clone();
+          current_message
+        };
+        Err(ActorError::of_panicked(payload, current_message))
+      }
+    }
+  }
+
+  fn clear_current_message(&mut self) {
+    let inner = mutex_lock_with_log!(self.inner, "clear_current_message");
+    let mut current_message = inner.current_message.lock().unwrap();
+    *current_message = None;
+  }
+
+  fn handle_failed(&mut self, self_ref: ActorRef<Msg>, child: ActorRef<AnyMessage>, error: ActorError, uid: u32) {
+    let (children, actor_opt, props) = {
+      let inner = mutex_lock_with_log!(self.inner, "handle_failed");
+      (inner.children.clone(), inner.actor.clone(), inner.props.clone())
+    };
+    let is_current_child = children
+      .get_child_state_by_ref(child.clone())
+      .and_then(|state| state.as_child_restart_stats().map(|stats| stats.uid() == uid))
+      .unwrap_or(false);
+    if !is_current_child {
+      log::debug!(
+        "handle_failed: dropping Failed({}) from unknown child {}",
+        error,
+        child.path()
+      );
+      return;
+    }
+    let strategy = props.supervisor_strategy().unwrap_or_else(|| match actor_opt {
+      Some(actor) => actor.borrow().supervisor_strategy(),
+      None => SupervisorStrategy::default(),
     });
+    if !strategy.handle_failure(&children, child.clone(), error.clone()) {
+      self.handle_invoke_failure(self_ref, Some(child), error);
+    }
   }
 
-  fn tell_terminated_to_parent(&mut self, self_ref: ActorRef<Msg>) {
+  pub(crate) fn tell_terminated_to_parent(&mut self, self_ref: ActorRef<Msg>) {
     let mut parent_ref_opt = {
       let inner = mutex_lock_with_log!(self.inner, "system_invoke");
       inner.parent_ref.clone()
     };
     if let Some(parent_ref) = &mut parent_ref_opt {
-      let terminated = AutoReceivedMessage::Terminated(self_ref.clone().to_any(false));
-      let msg = AnyMessage::new(terminated);
-      parent_ref.tell_any(msg);
-    } else {
-      let mut tx_g = self.tx.lock().unwrap();
-      let terminated_tx = tx_g.take().unwrap();
-      terminated_tx.send(()).unwrap()
+      parent_ref.send_system_message(&mut SystemMessageEntry::new(SystemMessage::of_death_watch_notification(
+        self_ref.to_any(false),
+        true,
+        false,
+      )));
     }
+    self.notify_terminated();
   }
 }
 
@@ -621,10 +916,15 @@ impl<Msg: Message> ActorCell<Msg> {
 mod tests {
   use crate::core::actor::actor_cell::ActorCell;
   use crate::core::actor::actor_context::ActorContext;
-  use crate::core::actor::actor_path::ActorPath;
-  use crate::core::actor::actor_ref::ActorRef;
-  use crate::core::actor::props::Props;
-  use crate::core::actor::{ActorBehavior, ActorResult};
+  use crate::core::actor::actor_path::{ActorPath, ActorPathBehavior};
+  use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior};
+  use crate::core::actor::props::{FunctionProps, Props};
+  use crate::core::actor::supervisor_strategy::{Directive, SupervisorStrategy};
+  use crate::core::actor::behaviors::Behaviors;
+  use crate::core::actor::test_kit::{
+    start_system, DeadLetterListener, EventActor, EventProbe, SupervisingActor, EVENT_TIMEOUT,
+  };
+  use crate::core::actor::{ActorBehavior, ActorError, ActorResult};
   use crate::core::dispatch::any_message::AnyMessage;
   use crate::core::dispatch::dispatcher::Dispatcher;
   use crate::core::dispatch::mailbox::mailbox_type::MailboxType;
@@ -633,7 +933,9 @@ mod tests {
   use std::cell::RefCell;
   use std::env;
   use std::rc::Rc;
+  use std::sync::mpsc::channel;
   use std::sync::{Arc, Mutex};
+  use std::time::Duration;
 
   #[derive(Debug, Clone)]
   struct TestActor;
@@ -650,6 +952,15 @@ mod tests {
     }
   }
 
+  #[derive(Debug, Clone)]
+  struct PanickingChildActor;
+
+  impl ActorBehavior<String> for PanickingChildActor {
+    fn receive(&mut self, _ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+      panic!("panicked on {}", msg);
+    }
+  }
+
   #[derive(Debug, Clone)]
   struct TestProps;
 
@@ -684,10 +995,97 @@ mod tests {
       MailboxType::Unbounded,
       ActorRef::of_dead_letters(ActorPath::from_string("test://test")),
     );
-    let dispatcher = Dispatcher::new(Arc::new(runtime), Arc::new(Mutex::new(mailboxes)));
+    let dispatcher = Dispatcher::new(runtime.handle().clone(), Arc::new(Mutex::new(mailboxes)));
     let path = ActorPath::from_string("test://test");
     let ac: ActorCell<String> = ActorCell::new(dispatcher, path, Rc::new(TestProps {}), None);
     let to_any = ac.to_any(false);
     let _org = to_any.to_typed::<String>(false);
   }
+
+  #[test]
+  fn test_panic_in_receive_is_reported_to_supervisor() {
+    let (errors_tx, errors_rx) = channel();
+    let (terminated_tx, terminated_rx) = channel();
+    let main_props = FunctionProps::of_actor_with_args((errors_tx, terminated_tx), |(errors_tx, terminated_tx)| {
+      let errors_tx = errors_tx.clone();
+      let strategy = SupervisorStrategy::of_one_for_one(None, None, move |error| {
+        errors_tx.send(error.clone()).unwrap();
+        Directive::Stop
+      });
+      SupervisingActor::new(
+        Rc::new(FunctionProps::of_actor(|| PanickingChildActor)),
+        strategy,
+        terminated_tx.clone(),
+      )
+    });
+    let (_actor_system, mut supervising_ref) = start_system(Rc::new(main_props));
+
+    supervising_ref.tell("boom".to_string());
+    match errors_rx.recv_timeout(EVENT_TIMEOUT).unwrap() {
+      ActorError::ActorPanicked {
+        payload,
+        current_message,
+      } => {
+        assert_eq!(payload, "panicked on boom");
+        assert_eq!(current_message.unwrap().typed_message::<String>().unwrap(), "boom");
+      }
+      other => panic!("unexpected error: {:?}", other),
+    }
+    assert_eq!(terminated_rx.recv_timeout(EVENT_TIMEOUT), Ok("failing-child".to_string()));
+  }
+
+  #[test]
+  fn test_poison_pill_stops_after_queued_messages() {
+    let probe = EventProbe::new();
+    let (actor_system, mut parent_ref) = start_system(EventActor::props("parent", probe.events_tx(), None));
+    parent_ref.tell("a".to_string());
+    parent_ref.tell("b".to_string());
+    parent_ref.tell_poison_pill();
+    parent_ref.tell("c".to_string());
+
+    assert_eq!(
+      probe.next_n(4),
+      vec![
+        "parent:pre_start",
+        "parent:received:a",
+        "parent:received:b",
+        "parent:post_stop"
+      ]
+    );
+    actor_system.when_terminate();
+    probe.expect_no_event(Duration::from_millis(300));
+  }
+
+  #[test]
+  fn test_kill_is_handled_by_supervisor() {
+    let probe = EventProbe::new();
+    let child_props = EventActor::props("child", probe.events_tx(), None);
+    let parent_props = EventActor::props("parent", probe.events_tx(), Some(child_props));
+    let (_actor_system, mut parent_ref) = start_system(parent_props);
+    parent_ref.tell("start".to_string());
+    probe.wait_for("child:pre_start");
+
+    parent_ref.tell("child:kill".to_string());
+    assert_eq!(
+      probe.next_n_matching(1, |event| event.starts_with("child:")),
+      vec!["child:post_stop"]
+    );
+  }
+
+  #[test]
+  fn test_messages_of_another_type_go_to_dead_letters() {
+    let probe = EventProbe::new();
+    let (actor_system, main_ref) = start_system(EventActor::props("main", probe.events_tx(), None));
+    let dead_letters = EventProbe::new();
+    let listener_ref = actor_system.actor_of(DeadLetterListener::props(dead_letters.events_tx()), "listener");
+    actor_system.subscribe_dead_letters(listener_ref);
+    let counter_behavior = Behaviors::receive_message(|_ctx, _msg: u32| Ok(Behaviors::same()));
+    let counter_ref = actor_system.actor_of(Rc::new(counter_behavior.to_props()), "counter");
+
+    let sender = ActorRef::of_mock(main_ref.path().with_child("sender"));
+    counter_ref
+      .to_any(false)
+      .tell_with_sender(AnyMessage::new("text".to_string()), sender);
+    assert_eq!(dead_letters.next(), "text:sender->counter");
+  }
 }
@@ -0,0 +1,87 @@
+use crate::core::actor::actor_cell::ActorCell;
+use crate::core::actor::Receive;
+use crate::core::dispatch::message::Message;
+use crate::mutex_lock_with_log;
+
+impl<Msg: Message> ActorCell<Msg> {
+  /// Makes `handler` the current behavior; with `discard_old` it replaces the top of the stack instead of pushing.
+  pub(crate) fn r#become(&mut self, handler: Receive<Msg>, discard_old: bool) {
+    let mut inner = mutex_lock_with_log!(self.inner, "become");
+    if discard_old {
+      inner.behavior_stack.pop();
+    }
+    inner.behavior_stack.push(handler);
+  }
+
+  /// Reverts to the previous behavior, or to `receive` once the stack is empty.
+  pub(crate) fn unbecome(&mut self) {
+    let mut inner = mutex_lock_with_log!(self.inner, "unbecome");
+    inner.behavior_stack.pop();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::core::actor::actor_context::{ActorContext, ActorContextBehavior};
+  use crate::core::actor::actor_ref::ActorRefBehavior;
+  use crate::core::actor::props::FunctionProps;
+  use crate::core::actor::test_kit::{start_system, EventProbe};
+  use crate::core::actor::{ActorBehavior, ActorResult};
+  use std::rc::Rc;
+  use std::sync::mpsc::Sender;
+
+  #[derive(Debug, Clone)]
+  struct ConnectionActor {
+    events_tx: Sender<String>,
+  }
+
+  impl ConnectionActor {
+    fn connected(events_tx: Sender<String>) -> impl FnMut(ActorContext<String>, String) -> ActorResult<()> {
+      move |mut ctx, msg| {
+        match msg.as_str() {
+          "secure" => {
+            let secure_events_tx = events_tx.clone();
+            ctx.r#become(
+              move |mut ctx: ActorContext<String>, msg: String| {
+                match msg.as_str() {
+                  "disconnect" => ctx.unbecome(),
+                  _ => secure_events_tx.send(format!("secure:{}", msg)).unwrap(),
+                }
+                Ok(())
+              },
+              true,
+            );
+          }
+          "disconnect" => ctx.unbecome(),
+          _ => events_tx.send(format!("connected:{}", msg)).unwrap(),
+        }
+        Ok(())
+      }
+    }
+  }
+
+  impl ActorBehavior<String> for ConnectionActor {
+    fn receive(&mut self, mut ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+      match msg.as_str() {
+        "connect" => ctx.r#become(Self::connected(self.events_tx.clone()), false),
+        _ => self.events_tx.send(format!("received:{}", msg)).unwrap(),
+      }
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn test_become_and_unbecome_switch_the_receive_handler() {
+    let probe = EventProbe::new();
+    let events_tx = probe.events_tx();
+    let props = Rc::new(FunctionProps::of_actor(move || ConnectionActor {
+      events_tx: events_tx.clone(),
+    }));
+    let (_actor_system, mut connection_ref) = start_system(props);
+    for msg in ["a", "connect", "b", "secure", "c", "disconnect", "d"] {
+      connection_ref.tell(msg.to_string());
+    }
+
+    assert_eq!(probe.next_n(4), vec!["received:a", "connected:b", "secure:c", "received:d"]);
+  }
+}
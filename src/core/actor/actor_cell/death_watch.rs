@@ -0,0 +1,299 @@
+use crate::core::actor::actor_cell::ActorCell;
+use crate::core::actor::actor_context::ActorContext;
+use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior};
+use crate::core::dispatch::any_message::AnyMessage;
+use crate::core::dispatch::message::Message;
+use crate::core::dispatch::system_message::system_message::SystemMessage;
+use crate::core::dispatch::system_message::system_message_entry::SystemMessageEntry;
+use crate::mutex_lock_with_log;
+
+impl<Msg: Message> ActorCell<Msg> {
+  pub(crate) fn watch(&mut self, self_ref: ActorRef<Msg>, mut subject: ActorRef<AnyMessage>, msg: Option<Msg>) {
+    let self_any_ref = self_ref.clone().to_any(false);
+    if subject == self_any_ref {
+      return;
+    }
+    {
+      let mut inner = mutex_lock_with_log!(self.inner, "watch");
+      if let Some(watching) = inner.watching.iter_mut().find(|(watchee, _)| *watchee == subject) {
+        watching.1 = msg;
+        return;
+      }
+      inner.watching.push((subject.clone(), msg));
+    }
+    let subject_terminated = match subject.actor_cell() {
+      Some(actor_cell) => actor_cell.is_terminated(),
+      None => matches!(subject, ActorRef::DeadLetters(..)),
+    };
+    if subject_terminated {
+      let mut self_ref = self_ref;
+      self_ref.send_system_message(&mut SystemMessageEntry::new(SystemMessage::of_death_watch_notification(
+        subject, true, false,
+      )));
+    } else {
+      subject.send_system_message(&mut SystemMessageEntry::new(SystemMessage::of_watch(
+        subject.clone(),
+        self_any_ref,
+      )));
+    }
+  }
+
+  pub(crate) fn unwatch(&mut self, self_ref: ActorRef<Msg>, mut subject: ActorRef<AnyMessage>) {
+    let removed = {
+      let mut inner = mutex_lock_with_log!(self.inner, "unwatch");
+      let before = inner.watching.len();
+      inner.watching.retain(|(watchee, _)| *watchee != subject);
+      before != inner.watching.len()
+    };
+    if removed {
+      subject.send_system_message(&mut SystemMessageEntry::new(SystemMessage::of_unwatch(
+        subject.clone(),
+        self_ref.to_any(false),
+      )));
+    }
+  }
+
+  pub(crate) fn add_watcher(
+    &mut self,
+    self_ref: ActorRef<Msg>,
+    watchee: ActorRef<AnyMessage>,
+    mut watcher: ActorRef<AnyMessage>,
+  ) {
+    let self_any_ref = self_ref.to_any(false);
+    if watchee != self_any_ref || watcher == self_any_ref {
+      log::warn!("add_watcher: ignoring Watch({}, {})", watchee.path(), watcher.path());
+      return;
+    }
+    if self.is_terminated() {
+      watcher.send_system_message(&mut SystemMessageEntry::new(SystemMessage::of_death_watch_notification(
+        self_any_ref,
+        true,
+        false,
+      )));
+      return;
+    }
+    let mut inner = mutex_lock_with_log!(self.inner, "add_watcher");
+    if !inner.watched_by.contains(&watcher) {
+      inner.watched_by.push(watcher);
+    }
+  }
+
+  pub(crate) fn remove_watcher(&mut self, watcher: ActorRef<AnyMessage>) {
+    let mut inner = mutex_lock_with_log!(self.inner, "remove_watcher");
+    inner.watched_by.retain(|watched_by| *watched_by != watcher);
+  }
+
+  pub(crate) fn watched_actor_terminated(&mut self, mut self_ref: ActorRef<Msg>, actor: ActorRef<AnyMessage>) {
+    let (watching, is_child, is_terminating) = {
+      let mut inner = mutex_lock_with_log!(self.inner, "watched_actor_terminated");
+      let position = inner.watching.iter().position(|(watchee, _)| *watchee == actor);
+      let watching = position.map(|position| inner.watching.remove(position));
+      let is_child = inner.children.get_child_state_by_ref(actor.clone()).is_some();
+      (watching, is_child, inner.children.is_terminating())
+    };
+    match watching {
+      Some(_) if is_terminating => {
+        log::debug!("watched_actor_terminated: ignoring {} while terminating", actor.path());
+      }
+      Some((_, Some(msg))) => self_ref.tell(msg),
+      Some((_, None)) => {
+        if let Some(actor_rc) = self.actor() {
+          let ctx = ActorContext::new(self.clone(), self_ref.clone());
+          let result = self.catch_panic(|| actor_rc.borrow_mut().around_terminated(ctx, actor.clone()));
+          if let Err(error) = result {
+            self.handle_invoke_failure(self_ref.clone(), None, error);
+          }
+        }
+      }
+      None if !is_child => {
+        log::debug!("watched_actor_terminated: not watching {}", actor.path());
+      }
+      None => {}
+    }
+    if is_child {
+      self.handle_child_terminated(self_ref, actor);
+    }
+  }
+
+  pub(crate) fn tell_watchers_we_died(&mut self, self_ref: ActorRef<Msg>) {
+    let watched_by = {
+      let mut inner = mutex_lock_with_log!(self.inner, "tell_watchers_we_died");
+      std::mem::take(&mut inner.watched_by)
+    };
+    let self_any_ref = self_ref.to_any(false);
+    for mut watcher in watched_by {
+      watcher.send_system_message(&mut SystemMessageEntry::new(SystemMessage::of_death_watch_notification(
+        self_any_ref.clone(),
+        true,
+        false,
+      )));
+    }
+  }
+
+  pub(crate) fn unwatch_watched_actors(&mut self, self_ref: ActorRef<Msg>) {
+    let watching = {
+      let mut inner = mutex_lock_with_log!(self.inner, "unwatch_watched_actors");
+      std::mem::take(&mut inner.watching)
+    };
+    let self_any_ref = self_ref.to_any(false);
+    for (mut watchee, _) in watching {
+      watchee.send_system_message(&mut SystemMessageEntry::new(SystemMessage::of_unwatch(
+        watchee.clone(),
+        self_any_ref.clone(),
+      )));
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::core::actor::actor_context::{ActorContext, ActorContextBehavior};
+  use crate::core::actor::actor_path::ActorPathBehavior;
+  use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior};
+  use crate::core::actor::actor_system::ActorSystem;
+  use crate::core::actor::props::FunctionProps;
+  use crate::core::actor::test_kit::{start_system, EventActor, EventProbe, ReportingActor};
+  use crate::core::actor::{ActorBehavior, ActorResult};
+  use crate::core::dispatch::any_message::AnyMessage;
+  use crate::core::dispatch::system_message::system_message::SystemMessage;
+  use crate::core::dispatch::system_message::system_message_entry::SystemMessageEntry;
+  use crate::core::dispatch::system_message::SystemMessageQueueWriterBehavior;
+  use std::rc::Rc;
+  use std::sync::mpsc::Sender;
+
+  #[derive(Debug, Clone)]
+  struct WatchingActor {
+    name: String,
+    target: ActorRef<String>,
+    custom_message: Option<String>,
+    events_tx: Sender<String>,
+  }
+
+  impl ActorBehavior<String> for WatchingActor {
+    fn receive(&mut self, _ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+      self.events_tx.send(format!("{}:received:{}", self.name, msg)).unwrap();
+      Ok(())
+    }
+
+    fn pre_start(&mut self, mut ctx: ActorContext<String>) -> ActorResult<()> {
+      match self.custom_message.clone() {
+        Some(msg) => ctx.watch_with(&self.target, msg),
+        None => ctx.watch(&self.target),
+      }
+      self.events_tx.send(format!("{}:watching", self.name)).unwrap();
+      Ok(())
+    }
+
+    fn terminated(&mut self, _ctx: ActorContext<String>, actor: ActorRef<AnyMessage>) -> ActorResult<()> {
+      self
+        .events_tx
+        .send(format!("{}:terminated:{}", self.name, actor.path().name()))
+        .unwrap();
+      Ok(())
+    }
+  }
+
+  /// Spawns `watched` and a `watcher` of it; `watch-late` adds a watcher once `watched` has stopped.
+  #[derive(Debug, Clone)]
+  struct DeathWatchRootActor {
+    custom_message: Option<String>,
+    events_tx: Sender<String>,
+    watched_ref: Option<ActorRef<String>>,
+  }
+
+  impl DeathWatchRootActor {
+    fn spawn_watcher(&self, ctx: &mut ActorContext<String>, name: &str) {
+      let watching_actor = WatchingActor {
+        name: name.to_string(),
+        target: self.watched_ref.clone().unwrap(),
+        custom_message: self.custom_message.clone(),
+        events_tx: self.events_tx.clone(),
+      };
+      ctx.spawn(Rc::new(FunctionProps::of_actor(move || watching_actor.clone())), name);
+    }
+  }
+
+  impl ActorBehavior<String> for DeathWatchRootActor {
+    fn receive(&mut self, mut ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+      match msg.as_str() {
+        "stop-watched" => ctx.stop(self.watched_ref.clone().unwrap()),
+        "watch-late" => self.spawn_watcher(&mut ctx, "late-watcher"),
+        _ => {}
+      }
+      Ok(())
+    }
+
+    fn pre_start(&mut self, mut ctx: ActorContext<String>) -> ActorResult<()> {
+      let watched_props = EventActor::props("watched", self.events_tx.clone(), None);
+      self.watched_ref = Some(ctx.spawn(watched_props, "watched"));
+      self.spawn_watcher(&mut ctx, "watcher");
+      Ok(())
+    }
+  }
+
+  fn start_death_watch_system(
+    probe: &EventProbe,
+    custom_message: Option<String>,
+  ) -> (ActorSystem<String>, ActorRef<String>) {
+    let main_props = FunctionProps::of_actor_with_args(probe.events_tx(), move |events_tx| DeathWatchRootActor {
+      custom_message: custom_message.clone(),
+      events_tx: events_tx.clone(),
+      watched_ref: None,
+    });
+    start_system(Rc::new(main_props))
+  }
+
+  #[test]
+  fn test_watch_delivers_terminated_to_non_parent_watcher() {
+    let probe = EventProbe::new();
+    let (_actor_system, mut root_ref) = start_death_watch_system(&probe, None);
+    probe.wait_for("watcher:watching");
+
+    root_ref.tell("stop-watched".to_string());
+    probe.wait_for("watcher:terminated:watched");
+
+    root_ref.tell("watch-late".to_string());
+    probe.wait_for("late-watcher:watching");
+    probe.wait_for("late-watcher:terminated:watched");
+  }
+
+  #[test]
+  fn test_watch_reaching_dead_letters_delivers_terminated() {
+    let probe = EventProbe::new();
+    let reporting_props = Rc::new(FunctionProps::of_actor_with_args(probe.events_tx(), |events_tx| {
+      ReportingActor::new(events_tx.clone())
+    }));
+    let (actor_system, _main_ref) = start_system(reporting_props.clone());
+    let target = actor_system.actor_of(reporting_props, "target");
+    let watching_actor = WatchingActor {
+      name: "watcher".to_string(),
+      target: target.clone(),
+      custom_message: None,
+      events_tx: probe.events_tx(),
+    };
+    let watcher_props = FunctionProps::of_actor(move || watching_actor.clone());
+    let watcher = actor_system.actor_of(Rc::new(watcher_props), "watcher");
+    probe.wait_for("watcher:watching");
+
+    // A Watch that ends up in dead letters, as it does when the watchee stops before handling it.
+    let mut dead_letter_mailbox = target.actor_cell().unwrap().dead_letter_mailbox();
+    dead_letter_mailbox.system_enqueue(
+      target.clone().to_any(false),
+      &mut SystemMessageEntry::new(SystemMessage::of_watch(
+        target.clone().to_any(false),
+        watcher.to_any(false),
+      )),
+    );
+    probe.wait_for("watcher:terminated:target");
+  }
+
+  #[test]
+  fn test_watch_with_delivers_custom_message() {
+    let probe = EventProbe::new();
+    let (_actor_system, mut root_ref) = start_death_watch_system(&probe, Some("target-gone".to_string()));
+    probe.wait_for("watcher:watching");
+
+    root_ref.tell("stop-watched".to_string());
+    probe.wait_for("watcher:received:target-gone");
+  }
+}
@@ -0,0 +1,425 @@
+use crate::core::actor::actor_cell::ActorCell;
+use crate::core::actor::actor_cell_with_ref::ActorCellWithRef;
+use crate::core::actor::actor_context::ActorContext;
+use crate::core::actor::actor_path::ActorPathBehavior;
+use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior};
+use crate::core::actor::children_refs::SuspendReason;
+use crate::core::actor::ActorError;
+use crate::core::dispatch::any_message::AnyMessage;
+use crate::core::dispatch::dispatcher::DispatcherBehavior;
+use crate::core::dispatch::message::Message;
+use crate::core::dispatch::system_message::system_message::SystemMessage;
+use crate::core::dispatch::system_message::system_message_entry::SystemMessageEntry;
+use crate::mutex_lock_with_log;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FailedInfo {
+  NoFailedInfo,
+  FailedRef(Box<ActorRef<AnyMessage>>),
+  FailedFatally,
+}
+
+impl<Msg: Message> ActorCell<Msg> {
+  pub fn is_failed(&self) -> bool {
+    let inner = mutex_lock_with_log!(self.inner, "is_failed");
+    matches!(inner.failed, FailedInfo::FailedRef(..))
+  }
+
+  pub fn is_failed_fatally(&self) -> bool {
+    let inner = mutex_lock_with_log!(self.inner, "is_failed_fatally");
+    matches!(inner.failed, FailedInfo::FailedFatally)
+  }
+
+  pub fn perpetrator(&self) -> Option<ActorRef<AnyMessage>> {
+    let inner = mutex_lock_with_log!(self.inner, "perpetrator");
+    match &inner.failed {
+      FailedInfo::FailedRef(perpetrator) => Some(*perpetrator.clone()),
+      _ => None,
+    }
+  }
+
+  fn set_failed(&mut self, perpetrator: ActorRef<AnyMessage>) {
+    let mut inner = mutex_lock_with_log!(self.inner, "set_failed");
+    if inner.failed != FailedInfo::FailedFatally {
+      inner.failed = FailedInfo::FailedRef(Box::new(perpetrator));
+    }
+  }
+
+  fn clear_failed(&mut self) {
+    let mut inner = mutex_lock_with_log!(self.inner, "clear_failed");
+    if let FailedInfo::FailedRef(..) = inner.failed {
+      inner.failed = FailedInfo::NoFailedInfo;
+    }
+  }
+
+  pub(crate) fn set_failed_fatally(&mut self) {
+    let mut inner = mutex_lock_with_log!(self.inner, "set_failed_fatally");
+    inner.failed = FailedInfo::FailedFatally;
+  }
+
+  pub(crate) fn fault_recreate(&mut self, self_ref: ActorRef<Msg>, cause: ActorError) {
+    let (actor_opt, mut children, current_message) = {
+      let inner = mutex_lock_with_log!(self.inner, "fault_recreate");
+      let current_message = inner.current_message.lock().unwrap().clone();
+      (inner.actor.clone(), inner.children.clone(), current_message)
+    };
+    match actor_opt {
+      None => self.fault_create(self_ref),
+      Some(actor) => {
+        if !self.is_failed_fatally() {
+          let ctx = ActorContext::new(self.clone(), self_ref.clone());
+          let msg = current_message.and_then(|envelope| envelope.typed_message::<Msg>().ok());
+          let result = self.catch_panic(|| actor.borrow_mut().around_pre_restart(ctx, cause.clone(), msg));
+          if let Err(error) = result {
+            log::error!("fault_recreate: actor({}) pre_restart failed: {}", self_ref.path(), error);
+          }
+        }
+        self.clear_current_message();
+        self.abort_tasks();
+        if let Err(error) = self.unstash_all(self_ref.clone()) {
+          log::error!("fault_recreate: actor({}) unstash_all failed: {}", self_ref.path(), error);
+        }
+        children.stop_all_children();
+        if !children.set_children_termination_reason(SuspendReason::Recreation { cause: cause.clone() }) {
+          self.finish_recreate(self_ref, cause);
+        }
+      }
+    }
+  }
+
+  pub(crate) fn fault_suspend(&mut self, self_ref: ActorRef<Msg>) {
+    if let Some(actor) = self.actor() {
+      let ctx = ActorContext::new(self.clone(), self_ref.clone());
+      if let Err(error) = self.catch_panic(|| actor.borrow_mut().around_pre_suspend(ctx)) {
+        log::error!("fault_suspend: actor({}) pre_suspend failed: {}", self_ref.path(), error);
+      }
+    }
+    self.suspend_non_recursive(self_ref);
+    self.suspend_children(None);
+  }
+
+  pub(crate) fn fault_resume(&mut self, self_ref: ActorRef<Msg>, caused_by_failure: Option<ActorError>) {
+    match self.actor() {
+      None => self.fault_create(self_ref),
+      Some(_) if self.is_failed_fatally() && caused_by_failure.is_some() => {
+        self.fault_recreate(self_ref, caused_by_failure.unwrap())
+      }
+      Some(actor) => {
+        let perpetrator = self.perpetrator();
+        self.resume_non_recursive(self_ref.clone());
+        if caused_by_failure.is_some() {
+          self.clear_failed();
+        }
+        let ctx = ActorContext::new(self.clone(), self_ref.clone());
+        let cause = caused_by_failure.clone();
+        if let Err(error) = self.catch_panic(|| actor.borrow_mut().around_post_resume(ctx, cause)) {
+          log::error!("fault_resume: actor({}) post_resume failed: {}", self_ref.path(), error);
+        }
+        self.resume_children(caused_by_failure, perpetrator);
+      }
+    }
+  }
+
+  pub(crate) fn fault_create(&mut self, self_ref: ActorRef<Msg>) {
+    let mut children = {
+      let inner = mutex_lock_with_log!(self.inner, "fault_create");
+      inner.children.clone()
+    };
+    children.stop_all_children();
+    if !children.set_children_termination_reason(SuspendReason::Creation) {
+      self.finish_create(self_ref);
+    }
+  }
+
+  pub(crate) fn finish_create(&mut self, self_ref: ActorRef<Msg>) {
+    self.resume_non_recursive(self_ref.clone());
+    {
+      let mut inner = mutex_lock_with_log!(self.inner, "finish_create");
+      inner.failed = FailedInfo::NoFailedInfo;
+    }
+    self.create(self_ref);
+  }
+
+  pub(crate) fn finish_recreate(&mut self, self_ref: ActorRef<Msg>, cause: ActorError) {
+    let fresh_actor = {
+      let mut inner = mutex_lock_with_log!(self.inner, "finish_recreate");
+      let fresh_actor = inner.props.new_actor();
+      inner.actor = Some(fresh_actor.clone());
+      inner.behavior_stack.clear();
+      fresh_actor
+    };
+    self.clear_failed();
+    self.resume_non_recursive(self_ref.clone());
+    let ctx = ActorContext::new(self.clone(), self_ref.clone());
+    let result = self.catch_panic(|| fresh_actor.borrow_mut().around_post_restart(ctx, cause));
+    if let Err(error) = result {
+      self.handle_invoke_failure(self_ref, None, error);
+    }
+  }
+
+  /// Stops the children first and finishes termination once the last of them has gone.
+  pub(crate) fn terminate(&mut self, self_ref: ActorRef<Msg>) {
+    let mut children = {
+      let inner = mutex_lock_with_log!(self.inner, "terminate");
+      inner.children.clone()
+    };
+    if self.is_terminated() || children.is_terminating() {
+      log::debug!("terminate: actor({}) is already terminating", self_ref.path());
+      return;
+    }
+    self.cancel_receive_timeout();
+    self.abort_tasks();
+    self.suspend_non_recursive(self_ref.clone());
+    children.stop_all_children();
+    if !children.set_children_termination_reason(SuspendReason::Termination) {
+      self.finish_terminate(self_ref);
+    }
+  }
+
+  pub(crate) fn finish_terminate(&mut self, self_ref: ActorRef<Msg>) {
+    if let Some(actor) = self.actor() {
+      let ctx = ActorContext::new(self.clone(), self_ref.clone());
+      if let Err(error) = self.catch_panic(|| actor.borrow_mut().around_post_stop(ctx)) {
+        log::error!("finish_terminate: actor({}) post_stop failed: {}", self_ref.path(), error);
+      }
+    } else {
+      log::warn!("finish_terminate: actor({}) is None", self_ref.path());
+    }
+    let mut dispatcher = {
+      let inner = mutex_lock_with_log!(self.inner, "finish_terminate");
+      inner.terminated.store(true, std::sync::atomic::Ordering::Relaxed);
+      inner.dispatcher.clone()
+    };
+    dispatcher.detach(ActorCellWithRef::new(self.clone(), self_ref.clone()));
+    self.clear_stash(self_ref.clone());
+    self.clear_message_adapters();
+    self.tell_watchers_we_died(self_ref.clone());
+    self.unwatch_watched_actors(self_ref.clone());
+    self.tell_terminated_to_parent(self_ref);
+    let mut inner = mutex_lock_with_log!(self.inner, "finish_terminate");
+    inner.children.clear();
+    inner.parent_ref.take();
+    inner.actor.take();
+    inner.behavior_stack.clear();
+  }
+
+  /// Removes a stopped child and resumes whatever was waiting for the children to go.
+  pub(crate) fn handle_child_terminated(&mut self, self_ref: ActorRef<Msg>, child: ActorRef<AnyMessage>) {
+    if let Some(actor) = self.actor() {
+      let ctx = ActorContext::new(self.clone(), self_ref.clone());
+      let result = self.catch_panic(|| actor.borrow_mut().around_child_terminated(ctx, child.clone()));
+      if let Err(error) = result {
+        self.handle_invoke_failure(self_ref.clone(), None, error);
+      }
+    }
+    let suspend_reason = {
+      let inner = mutex_lock_with_log!(self.inner, "handle_child_terminated");
+      let mut children = inner.children.clone();
+      children.un_reserve_child(child.path().name());
+      children.take_children_termination_reason()
+    };
+    match suspend_reason {
+      Some(SuspendReason::Recreation { cause }) => self.finish_recreate(self_ref, cause),
+      Some(SuspendReason::Creation) => self.finish_create(self_ref),
+      Some(SuspendReason::Termination) => self.finish_terminate(self_ref),
+      _ => {}
+    }
+  }
+
+  /// Suspends this actor and its children, then reports the failure to the parent.
+  ///
+  /// `perpetrator` is the child whose escalated failure is being handled, if any; it is already suspended.
+  pub(crate) fn handle_invoke_failure(
+    &mut self,
+    self_ref: ActorRef<Msg>,
+    perpetrator: Option<ActorRef<AnyMessage>>,
+    error: ActorError,
+  ) {
+    log::error!("handle_invoke_failure: actor({}) failed: {}", self_ref.path(), error);
+    if self.is_failed() {
+      return;
+    }
+    self.suspend_non_recursive(self_ref.clone());
+    self.set_failed(perpetrator.clone().unwrap_or_else(|| self_ref.clone().to_any(false)));
+    self.suspend_children(perpetrator);
+    let mut parent_ref_opt = {
+      let inner = mutex_lock_with_log!(self.inner, "handle_invoke_failure");
+      inner.parent_ref.clone()
+    };
+    match &mut parent_ref_opt {
+      Some(parent_ref) => {
+        let uid = self_ref.path().uid();
+        parent_ref.send_system_message(&mut SystemMessageEntry::new(SystemMessage::of_failed(
+          self_ref.to_any(false),
+          error,
+          uid,
+        )));
+      }
+      None => {
+        log::error!(
+          "handle_invoke_failure: actor({}) has no supervisor, stopping",
+          self_ref.path()
+        );
+        self.stop(self_ref);
+      }
+    }
+  }
+
+  fn suspend_children(&mut self, except_for: Option<ActorRef<AnyMessage>>) {
+    let children = {
+      let inner = mutex_lock_with_log!(self.inner, "suspend_children");
+      inner.children.children()
+    };
+    for mut child in children {
+      if Some(&child) != except_for.as_ref() {
+        child.send_system_message(&mut SystemMessageEntry::new(SystemMessage::of_suspend()));
+      }
+    }
+  }
+
+  fn resume_children(&mut self, caused_by_failure: Option<ActorError>, perpetrator: Option<ActorRef<AnyMessage>>) {
+    let children = {
+      let inner = mutex_lock_with_log!(self.inner, "resume_children");
+      inner.children.children()
+    };
+    for mut child in children {
+      let cause = if Some(&child) == perpetrator.as_ref() {
+        caused_by_failure.clone()
+      } else {
+        None
+      };
+      child.send_system_message(&mut SystemMessageEntry::new(SystemMessage::of_resume_with_failure(
+        cause,
+      )));
+    }
+  }
+
+  fn suspend_non_recursive(&mut self, self_ref: ActorRef<Msg>) {
+    let mut dispatcher = {
+      let inner = mutex_lock_with_log!(self.inner, "suspend_non_recursive");
+      inner.dispatcher.clone()
+    };
+    dispatcher.suspend(ActorCellWithRef::new(self.clone(), self_ref));
+  }
+
+  fn resume_non_recursive(&mut self, self_ref: ActorRef<Msg>) {
+    let mut dispatcher = {
+      let inner = mutex_lock_with_log!(self.inner, "resume_non_recursive");
+      inner.dispatcher.clone()
+    };
+    dispatcher.resume(ActorCellWithRef::new(self.clone(), self_ref));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::core::actor::actor_context::ActorContext;
+  use crate::core::actor::actor_ref::ActorRefBehavior;
+  use crate::core::actor::props::FunctionProps;
+  use crate::core::actor::supervisor_strategy::{Directive, SupervisorStrategy};
+  use crate::core::actor::test_kit::{start_system, EventActor, EventProbe, SupervisingActor};
+  use crate::core::actor::{ActorBehavior, ActorError, ActorResult};
+  use std::rc::Rc;
+  use std::sync::mpsc::{channel, Sender};
+  use std::time::Duration;
+
+  #[derive(Debug, Clone)]
+  struct RestartingChildActor {
+    events_tx: Sender<String>,
+  }
+
+  impl ActorBehavior<String> for RestartingChildActor {
+    fn receive(&mut self, _ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+      if msg == "boom" {
+        return Err(ActorError::ActorFailed { message: msg });
+      }
+      self.events_tx.send(format!("received:{}", msg)).unwrap();
+      Ok(())
+    }
+
+    fn pre_start(&mut self, _ctx: ActorContext<String>) -> ActorResult<()> {
+      self.events_tx.send("pre_start".to_string()).unwrap();
+      Ok(())
+    }
+
+    fn pre_restart(&mut self, _ctx: ActorContext<String>, _reason: ActorError, msg: Option<String>) -> ActorResult<()> {
+      self.events_tx.send(format!("pre_restart:{}", msg.unwrap())).unwrap();
+      Ok(())
+    }
+
+    fn post_restart(&mut self, _ctx: ActorContext<String>, _reason: ActorError) -> ActorResult<()> {
+      self.events_tx.send("post_restart".to_string()).unwrap();
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn test_restart_recreates_failed_child() {
+    let probe = EventProbe::new();
+    let (terminated_tx, _terminated_rx) = channel();
+    let child_props = Rc::new(FunctionProps::of_actor_with_args(probe.events_tx(), |events_tx| {
+      RestartingChildActor {
+        events_tx: events_tx.clone(),
+      }
+    }));
+    let main_props = FunctionProps::of_actor_with_args(terminated_tx, move |terminated_tx| {
+      SupervisingActor::new(
+        child_props.clone(),
+        SupervisorStrategy::of_one_for_one(None, None, |_| Directive::Restart),
+        terminated_tx.clone(),
+      )
+    });
+    let (_actor_system, mut supervising_ref) = start_system(Rc::new(main_props));
+
+    supervising_ref.tell("boom".to_string());
+    supervising_ref.tell("ping".to_string());
+    assert_eq!(
+      probe.next_n(4),
+      vec!["pre_start", "pre_restart:boom", "post_restart", "received:ping"]
+    );
+  }
+
+  #[test]
+  fn test_suspend_and_resume_propagate_to_children() {
+    let probe = EventProbe::new();
+    let child_props = EventActor::props("child", probe.events_tx(), None);
+    let parent_props = EventActor::props("parent", probe.events_tx(), Some(child_props));
+    let (_actor_system, mut parent_ref) = start_system(parent_props);
+
+    parent_ref.tell("start".to_string());
+    assert_eq!(
+      probe.next_n_sorted(3),
+      vec!["child:pre_start", "parent:pre_start", "parent:received:start"]
+    );
+
+    parent_ref.suspend();
+    assert_eq!(probe.next_n(2), vec!["parent:pre_suspend", "child:pre_suspend"]);
+
+    parent_ref.tell("child:hello".to_string());
+    probe.expect_no_event(Duration::from_millis(300));
+
+    parent_ref.resume(None);
+    assert_eq!(
+      probe.next_n(3),
+      vec!["parent:post_resume", "child:post_resume", "child:received:hello"]
+    );
+  }
+
+  #[test]
+  fn test_terminate_waits_for_children_before_post_stop() {
+    let probe = EventProbe::new();
+    let grandchild_props = EventActor::props("grandchild", probe.events_tx(), None);
+    let child_props = EventActor::props("child", probe.events_tx(), Some(grandchild_props));
+    let parent_props = EventActor::props("parent", probe.events_tx(), Some(child_props));
+    let (actor_system, mut parent_ref) = start_system(parent_props);
+    parent_ref.tell("start".to_string());
+    probe.wait_for("grandchild:pre_start");
+
+    parent_ref.stop();
+    assert_eq!(
+      probe.next_n_matching(3, |event| event.ends_with(":post_stop")),
+      vec!["grandchild:post_stop", "child:post_stop", "parent:post_stop"]
+    );
+    actor_system.when_terminate();
+  }
+}
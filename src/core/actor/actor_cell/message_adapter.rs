@@ -0,0 +1,150 @@
+use crate::core::actor::actor_cell::ActorCell;
+use crate::core::actor::actor_path::ActorPathBehavior;
+use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior};
+use crate::core::dispatch::any_message::AnyMessage;
+use crate::core::dispatch::message::Message;
+use crate::mutex_lock_with_log;
+use std::any::TypeId;
+use std::rc::Rc;
+
+pub(crate) type MessageAdapter<Msg> = Rc<dyn Fn(AnyMessage) -> Option<Msg>>;
+
+impl<Msg: Message> ActorCell<Msg> {
+  /// Registers `f` as the adapter for `U`, replacing the one registered earlier, and returns a reference that
+  /// delivers `U` to this actor; if `self_ref` is not local, there is no owner to deliver to and dead letters is
+  /// returned instead.
+  pub(crate) fn message_adapter<U, F>(&self, self_ref: ActorRef<Msg>, f: F) -> ActorRef<U>
+  where
+    U: Message,
+    F: Fn(U) -> Msg + 'static, {
+    self.filtering_message_adapter(self_ref, move |message| Some(f(message)))
+  }
+
+  /// Like `message_adapter`, but messages for which `f` returns `None` are dropped.
+  pub(crate) fn filtering_message_adapter<U, F>(&self, self_ref: ActorRef<Msg>, f: F) -> ActorRef<U>
+  where
+    U: Message,
+    F: Fn(U) -> Option<Msg> + 'static, {
+    let type_id = TypeId::of::<U>();
+    let adapter: MessageAdapter<Msg> = Rc::new(move |message: AnyMessage| message.take::<U>().ok().and_then(&f));
+    let index = {
+      let mut inner = mutex_lock_with_log!(self.inner, "message_adapter");
+      match inner.message_adapters.iter().position(|(id, _)| *id == type_id) {
+        Some(index) => {
+          inner.message_adapters[index].1 = adapter;
+          index
+        }
+        None => {
+          inner.message_adapters.push((type_id, adapter));
+          inner.message_adapters.len() - 1
+        }
+      }
+    };
+    let path = self_ref.path().with_child(&format!("$adapter-{}", index));
+    match self_ref.to_any(true).as_local().cloned() {
+      Some(owner) => ActorRef::of_adapter(owner, path),
+      None => {
+        log::error!("message_adapter: actor({}) is not local, so its adapter is dead letters", path.parent());
+        self.dead_letter_mailbox().dead_letters().to_typed(false)
+      }
+    }
+  }
+
+  /// Converts a message received through an adapter with the adapter currently registered for its type.
+  pub(crate) fn adapt(&self, type_id: TypeId, message: AnyMessage) -> Option<Msg> {
+    let adapter = {
+      let inner = mutex_lock_with_log!(self.inner, "adapt");
+      inner
+        .message_adapters
+        .iter()
+        .find(|(id, _)| *id == type_id)
+        .map(|(_, adapter)| adapter.clone())
+    };
+    let message = message.take::<AnyMessage>().unwrap_or(message);
+    match adapter {
+      Some(adapter) => adapter(message),
+      None => {
+        log::warn!("adapt: no message adapter registered for {:?}", type_id);
+        None
+      }
+    }
+  }
+
+  pub(crate) fn clear_message_adapters(&mut self) {
+    let mut inner = mutex_lock_with_log!(self.inner, "clear_message_adapters");
+    inner.message_adapters.clear();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::core::actor::actor_context::{ActorContext, ActorContextBehavior};
+  use crate::core::actor::props::FunctionProps;
+  use crate::core::actor::test_kit::{block_on, start_system, EventProbe, EVENT_TIMEOUT};
+  use crate::core::actor::{ActorBehavior, ActorResult};
+  use std::sync::mpsc::{channel, Sender};
+  use std::time::Duration;
+
+  #[derive(Debug, Clone)]
+  struct AdaptingActor {
+    events_tx: Sender<String>,
+    adapters_tx: Sender<ActorRef<usize>>,
+  }
+
+  impl ActorBehavior<String> for AdaptingActor {
+    fn pre_start(&mut self, ctx: ActorContext<String>) -> ActorResult<()> {
+      let first = ctx.message_adaptor(|n: usize| format!("first:{}", n));
+      let second = ctx.message_adapter(|n: usize| format!("second:{}", n));
+      self.adapters_tx.send(first).unwrap();
+      self.adapters_tx.send(second).unwrap();
+      Ok(())
+    }
+
+    fn receive(&mut self, _ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+      self.events_tx.send(format!("received:{}", msg)).unwrap();
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn test_message_adapter_translates_into_owner_protocol() {
+    let probe = EventProbe::new();
+    let events_tx = probe.events_tx();
+    let (adapters_tx, adapters_rx) = channel();
+    let props = Rc::new(FunctionProps::of_actor(move || AdaptingActor {
+      events_tx: events_tx.clone(),
+      adapters_tx: adapters_tx.clone(),
+    }));
+    let (_actor_system, mut owner_ref) = start_system(props);
+    owner_ref.tell("start".to_string());
+    probe.wait_for("received:start");
+    let mut first = adapters_rx.recv_timeout(EVENT_TIMEOUT).unwrap();
+    let mut second = adapters_rx.recv_timeout(EVENT_TIMEOUT).unwrap();
+    assert_eq!(first.path(), second.path());
+
+    first.tell(1);
+    second.tell(2);
+    assert_eq!(probe.next_n(2), vec!["received:second:1", "received:second:2"]);
+
+    assert_eq!(block_on(owner_ref.graceful_stop(EVENT_TIMEOUT, None)), Ok(()));
+    second.tell(3);
+    probe.expect_no_event(Duration::from_millis(300));
+  }
+
+  #[test]
+  fn test_message_adapter_of_a_non_local_owner_is_dead_letters() {
+    let probe = EventProbe::new();
+    let events_tx = probe.events_tx();
+    let (adapters_tx, _adapters_rx) = channel();
+    let props = Rc::new(FunctionProps::of_actor(move || AdaptingActor {
+      events_tx: events_tx.clone(),
+      adapters_tx: adapters_tx.clone(),
+    }));
+    let (actor_system, owner_ref) = start_system(props);
+    let mock_ref = ActorRef::of_mock(owner_ref.path().with_child("mock"));
+
+    let adapter = owner_ref.actor_cell().unwrap().message_adapter(mock_ref, |n: usize| n.to_string());
+    assert_eq!(adapter.to_any(false), actor_system.dead_letters());
+  }
+}
@@ -0,0 +1,86 @@
+use crate::core::actor::actor_cell::ActorCell;
+use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior};
+use crate::core::dispatch::envelope::Envelope;
+use crate::core::dispatch::message::Message;
+use crate::mutex_lock_with_log;
+use std::future::Future;
+
+impl<Msg: Message> ActorCell<Msg> {
+  /// Runs `future` on the dispatcher's runtime and sends its result, mapped by `f`, to this actor.
+  ///
+  /// If the actor has stopped by the time `future` completes, the message goes to dead letters instead.
+  pub(crate) fn pipe_to_self<T, Fut, F>(&self, self_ref: ActorRef<Msg>, future: Fut, f: F)
+  where
+    T: Send + 'static,
+    Fut: Future<Output = T> + Send + 'static,
+    F: FnOnce(T) -> Msg + Send + 'static, {
+    let runtime = {
+      let inner = mutex_lock_with_log!(self.inner, "pipe_to_self");
+      inner.dispatcher.runtime()
+    };
+    let actor_cell = self.clone();
+    runtime.spawn(async move {
+      let msg = f(future.await);
+      let mut self_ref = self_ref;
+      if actor_cell.is_terminated() {
+        log::debug!("pipe_to_self: actor({}) has stopped, result goes to dead letters", self_ref.path());
+        let receiver = self_ref.to_any(false);
+        actor_cell.dead_letter_mailbox().enqueue(receiver, Envelope::new(msg));
+      } else {
+        self_ref.tell(msg);
+      }
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::core::actor::actor_context::{ActorContext, ActorContextBehavior};
+  use crate::core::actor::actor_ref::ActorRefBehavior;
+  use crate::core::actor::props::FunctionProps;
+  use crate::core::actor::test_kit::{block_on, start_system, EventProbe, EVENT_TIMEOUT};
+  use crate::core::actor::{ActorBehavior, ActorResult};
+  use std::rc::Rc;
+  use std::sync::mpsc::Sender;
+  use std::time::Duration;
+
+  #[derive(Debug, Clone)]
+  struct PipingActor {
+    events_tx: Sender<String>,
+  }
+
+  impl ActorBehavior<String> for PipingActor {
+    fn receive(&mut self, ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+      if let Some(delay) = msg.strip_prefix("fetch:") {
+        let delay = Duration::from_millis(delay.parse().unwrap());
+        ctx.pipe_to_self(
+          async move {
+            tokio::time::sleep(delay).await;
+            delay.as_millis()
+          },
+          |millis| format!("fetched:{}", millis),
+        );
+      }
+      self.events_tx.send(format!("received:{}", msg)).unwrap();
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn test_pipe_to_self_delivers_result_while_running() {
+    let probe = EventProbe::new();
+    let events_tx = probe.events_tx();
+    let props = Rc::new(FunctionProps::of_actor(move || PipingActor {
+      events_tx: events_tx.clone(),
+    }));
+    let (_actor_system, mut piping_ref) = start_system(props);
+    piping_ref.tell("fetch:100".to_string());
+    piping_ref.tell("next".to_string());
+    assert_eq!(probe.next_n(3), vec!["received:fetch:100", "received:next", "received:fetched:100"]);
+
+    piping_ref.tell("fetch:300".to_string());
+    probe.wait_for("received:fetch:300");
+    assert_eq!(block_on(piping_ref.graceful_stop(EVENT_TIMEOUT, None)), Ok(()));
+    probe.expect_no_event(Duration::from_millis(600));
+  }
+}
@@ -0,0 +1,144 @@
+use crate::core::actor::actor_cell::{ActorCell, AutoReceivedMessage};
+use crate::core::actor::actor_ref::{ActorRef, AnyActorRefBehavior};
+use crate::core::dispatch::any_message::AnyMessage;
+use crate::core::dispatch::message::Message;
+use crate::mutex_lock_with_log;
+use std::time::Duration;
+
+impl<Msg: Message> ActorCell<Msg> {
+  pub(crate) fn set_receive_timeout(&mut self, self_ref: ActorRef<Msg>, timeout: Duration, msg: Msg) {
+    {
+      let mut inner = mutex_lock_with_log!(self.inner, "set_receive_timeout");
+      inner.receive_timeout = Some((timeout, msg));
+    }
+    self.reschedule_receive_timeout(self_ref);
+  }
+
+  pub(crate) fn cancel_receive_timeout(&mut self) {
+    let task = {
+      let mut inner = mutex_lock_with_log!(self.inner, "cancel_receive_timeout");
+      inner.receive_timeout = None;
+      inner.receive_timeout_generation += 1;
+      inner.receive_timeout_task.take()
+    };
+    if let Some(task) = task {
+      task.cancel();
+    }
+  }
+
+  pub(crate) fn get_receive_timeout(&self) -> Option<Duration> {
+    let inner = mutex_lock_with_log!(self.inner, "get_receive_timeout");
+    inner.receive_timeout.as_ref().map(|(timeout, _)| *timeout)
+  }
+
+  /// Restarts the idle timer; called whenever a user message has been processed, whether or not it failed.
+  ///
+  /// Every reschedule starts a new generation, so that a timeout which was already queued in the mailbox when the
+  /// timer was restarted is dropped on delivery.
+  pub(crate) fn reschedule_receive_timeout(&mut self, self_ref: ActorRef<Msg>) {
+    let mut inner = mutex_lock_with_log!(self.inner, "reschedule_receive_timeout");
+    if let Some(task) = inner.receive_timeout_task.take() {
+      task.cancel();
+    }
+    inner.receive_timeout_generation += 1;
+    if let Some((timeout, _)) = inner.receive_timeout {
+      let runtime = inner.dispatcher.runtime();
+      let generation = inner.receive_timeout_generation;
+      let task = inner.dispatcher.scheduler().schedule_once(runtime, timeout, move || {
+        self_ref
+          .clone()
+          .tell_any(AnyMessage::new(AutoReceivedMessage::ReceiveTimeout { generation }));
+      });
+      inner.receive_timeout_task = Some(task);
+    }
+  }
+
+  /// Returns the timeout message if `generation` is the one last scheduled.
+  pub(crate) fn current_receive_timeout_message(&self, generation: u64) -> Option<Msg> {
+    let inner = mutex_lock_with_log!(self.inner, "current_receive_timeout_message");
+    match &inner.receive_timeout {
+      Some((_, msg)) if inner.receive_timeout_generation == generation => Some(msg.clone()),
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::core::actor::actor_cell::AutoReceivedMessage;
+  use crate::core::actor::actor_context::{ActorContext, ActorContextBehavior};
+  use crate::core::actor::actor_ref::{ActorRefBehavior, AnyActorRefBehavior};
+  use crate::core::actor::props::FunctionProps;
+  use crate::core::actor::test_kit::{start_system, EventProbe};
+  use crate::core::actor::{ActorBehavior, ActorResult};
+  use crate::core::dispatch::any_message::AnyMessage;
+  use std::rc::Rc;
+  use std::sync::mpsc::Sender;
+  use std::time::Duration;
+
+  #[derive(Debug, Clone)]
+  struct IdleActor {
+    events_tx: Sender<String>,
+  }
+
+  impl ActorBehavior<String> for IdleActor {
+    fn pre_start(&mut self, mut ctx: ActorContext<String>) -> ActorResult<()> {
+      ctx.set_receive_timeout(Duration::from_millis(300), "timeout".to_string());
+      Ok(())
+    }
+
+    fn receive(&mut self, mut ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+      if msg == "timeout" {
+        ctx.cancel_receive_timeout();
+        assert_eq!(ctx.get_receive_timeout(), None);
+      }
+      self.events_tx.send(format!("received:{}", msg)).unwrap();
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn test_receive_timeout_fires_after_idle_period() {
+    let probe = EventProbe::new();
+    let events_tx = probe.events_tx();
+    let props = Rc::new(FunctionProps::of_actor(move || IdleActor {
+      events_tx: events_tx.clone(),
+    }));
+    let (_actor_system, mut idle_ref) = start_system(props);
+    for i in 0..5 {
+      idle_ref.tell(format!("{}", i));
+      std::thread::sleep(Duration::from_millis(100));
+    }
+
+    assert_eq!(
+      probe.next_n(6),
+      vec![
+        "received:0",
+        "received:1",
+        "received:2",
+        "received:3",
+        "received:4",
+        "received:timeout"
+      ]
+    );
+    probe.expect_no_event(Duration::from_millis(600));
+  }
+
+  #[test]
+  fn test_timeout_queued_before_a_reschedule_is_dropped() {
+    let probe = EventProbe::new();
+    let events_tx = probe.events_tx();
+    let props = Rc::new(FunctionProps::of_actor(move || IdleActor {
+      events_tx: events_tx.clone(),
+    }));
+    let (_actor_system, mut idle_ref) = start_system(props);
+    idle_ref.tell("0".to_string());
+    // a timeout of the generation scheduled in pre_start, as if it had been queued before "0" restarted the timer
+    idle_ref.tell_any(AnyMessage::new(AutoReceivedMessage::ReceiveTimeout { generation: 1 }));
+
+    assert_eq!(probe.next(), "received:0");
+    probe.expect_no_event(Duration::from_millis(200));
+    assert_eq!(probe.next(), "received:timeout");
+    probe.expect_no_event(Duration::from_millis(600));
+  }
+}
@@ -0,0 +1,214 @@
+use crate::core::actor::actor_cell::{ActorCell, ActorCellBehavior};
+use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior};
+use crate::core::actor::ActorError;
+use crate::core::dispatch::mailbox::DequeMailboxWriterBehavior;
+use crate::core::dispatch::message::Message;
+use crate::mutex_lock_with_log;
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum StashError {
+  #[error("Stash overflow: capacity = {capacity}")]
+  Overflow { capacity: usize },
+  #[error("No current message to stash")]
+  NoCurrentMessage,
+  #[error("Stash requires a deque-based mailbox")]
+  UnsupportedMailbox,
+  #[error("Failed to unstash: {0}")]
+  UnstashFailed(String),
+}
+
+impl From<StashError> for ActorError {
+  fn from(error: StashError) -> Self {
+    ActorError::ActorFailed {
+      message: error.to_string(),
+    }
+  }
+}
+
+impl<Msg: Message> ActorCell<Msg> {
+  /// Holds the message being processed until it is put back by `unstash` or `unstash_all`.
+  pub(crate) fn stash(&mut self) -> Result<(), StashError> {
+    let mailbox_type = self.mailbox().mailbox_type();
+    if !mailbox_type.is_deque() {
+      return Err(StashError::UnsupportedMailbox);
+    }
+    let inner = mutex_lock_with_log!(self.inner, "stash");
+    let current_message = inner.current_message.lock().unwrap().clone();
+    let envelope = current_message.ok_or(StashError::NoCurrentMessage)?;
+    let mut stash = inner.stash.lock().unwrap();
+    if let Some(capacity) = mailbox_type.stash_capacity() {
+      if stash.len() >= capacity {
+        return Err(StashError::Overflow { capacity });
+      }
+    }
+    stash.push_back(envelope);
+    Ok(())
+  }
+
+  /// Puts the oldest `n` stashed messages back in front of the mailbox, keeping their order.
+  ///
+  /// If the mailbox refuses a message, the messages not put back yet stay at the front of the stash.
+  pub(crate) fn unstash(&mut self, self_ref: ActorRef<Msg>, n: usize) -> Result<usize, StashError> {
+    if !self.mailbox().mailbox_type().is_deque() {
+      return Err(StashError::UnsupportedMailbox);
+    }
+    let mut envelopes = {
+      let inner = mutex_lock_with_log!(self.inner, "unstash");
+      let mut stash = inner.stash.lock().unwrap();
+      let n = n.min(stash.len());
+      stash.drain(..n).collect::<Vec<_>>()
+    };
+    let count = envelopes.len();
+    if count == 0 {
+      return Ok(0);
+    }
+    let mut mailbox_sender = self.mailbox_sender();
+    while let Some(envelope) = envelopes.pop() {
+      if let Err(error) = mailbox_sender.enqueue_first(self_ref.clone(), envelope.clone()) {
+        log::error!("unstash: actor({}) failed to unstash: {}", self_ref.path(), error);
+        envelopes.push(envelope);
+        let inner = mutex_lock_with_log!(self.inner, "unstash");
+        let mut stash = inner.stash.lock().unwrap();
+        for envelope in envelopes.into_iter().rev() {
+          stash.push_front(envelope);
+        }
+        return Err(StashError::UnstashFailed(error.to_string()));
+      }
+    }
+    log::debug!("unstash: actor({}) unstashed {} messages", self_ref.path(), count);
+    Ok(count)
+  }
+
+  pub(crate) fn unstash_all(&mut self, self_ref: ActorRef<Msg>) -> Result<usize, StashError> {
+    let n = self.stash_size();
+    self.unstash(self_ref, n)
+  }
+
+  pub fn stash_size(&self) -> usize {
+    let inner = mutex_lock_with_log!(self.inner, "stash_size");
+    let size = inner.stash.lock().unwrap().len();
+    size
+  }
+
+  /// Sends the stashed messages to dead letters.
+  pub(crate) fn clear_stash(&mut self, self_ref: ActorRef<Msg>) {
+    let envelopes = {
+      let inner = mutex_lock_with_log!(self.inner, "clear_stash");
+      let envelopes = inner.stash.lock().unwrap().drain(..).collect::<Vec<_>>();
+      envelopes
+    };
+    if envelopes.is_empty() {
+      return;
+    }
+    log::debug!("clear_stash: actor({}) drops {} stashed messages", self_ref.path(), envelopes.len());
+    let mut dead_letter_mailbox = self.dead_letter_mailbox();
+    let receiver = self_ref.to_any(false);
+    for envelope in envelopes {
+      dead_letter_mailbox.enqueue(receiver.clone(), envelope);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::core::actor::actor_context::{ActorContext, ActorContextBehavior};
+  use crate::core::actor::actor_path::ActorPathBehavior;
+  use crate::core::actor::actor_system::ActorSystem;
+  use crate::core::actor::props::{FunctionProps, Props, PropsSettingsBehavior};
+  use crate::core::actor::test_kit::{
+    block_on, start_system, DeadLetterListener, EventProbe, ReportingActor, EVENT_TIMEOUT,
+  };
+  use crate::core::actor::{ActorBehavior, ActorResult};
+  use crate::core::dispatch::mailbox::mailbox_type::MailboxType;
+  use std::rc::Rc;
+  use std::sync::mpsc::Sender;
+
+  #[derive(Debug, Clone)]
+  struct StashingActor {
+    events_tx: Sender<String>,
+    initialized: bool,
+  }
+
+  impl StashingActor {
+    fn props(events_tx: Sender<String>, mailbox_type: MailboxType) -> Rc<dyn Props<String>> {
+      let props = FunctionProps::of_actor(move || StashingActor {
+        events_tx: events_tx.clone(),
+        initialized: false,
+      });
+      Rc::new(props.with_mailbox(mailbox_type))
+    }
+  }
+
+  impl ActorBehavior<String> for StashingActor {
+    fn receive(&mut self, mut ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+      match (self.initialized, msg.as_str()) {
+        (false, "init") => {
+          self.initialized = true;
+          let unstashed = ctx.unstash_all()?;
+          self.events_tx.send(format!("unstashed:{}", unstashed)).unwrap();
+        }
+        (false, _) => {
+          if let Err(error) = ctx.stash() {
+            self.events_tx.send(format!("stash_failed:{}:{}", msg, error)).unwrap();
+          }
+        }
+        (true, _) => self.events_tx.send(format!("received:{}", msg)).unwrap(),
+      }
+      Ok(())
+    }
+  }
+
+  fn start_stashing_system(probe: &EventProbe, mailbox_type: MailboxType) -> (ActorSystem<String>, ActorRef<String>) {
+    start_system(StashingActor::props(probe.events_tx(), mailbox_type))
+  }
+
+  #[test]
+  fn test_unstash_all_keeps_original_order() {
+    let probe = EventProbe::new();
+    let (_actor_system, mut stashing_ref) = start_stashing_system(&probe, MailboxType::of_unbounded_deque());
+    stashing_ref.tell("a".to_string());
+    stashing_ref.tell("b".to_string());
+    stashing_ref.tell("init".to_string());
+    stashing_ref.tell("c".to_string());
+
+    assert_eq!(probe.next_n(4), vec!["unstashed:2", "received:a", "received:b", "received:c"]);
+  }
+
+  #[test]
+  fn test_stopping_sends_stashed_messages_to_dead_letters() {
+    let probe = EventProbe::new();
+    let (actor_system, _) = start_system(ReportingActor::props(probe.events_tx()));
+    let dead_letters = EventProbe::new();
+    let listener_ref = actor_system.actor_of(DeadLetterListener::props(dead_letters.events_tx()), "listener");
+    actor_system.subscribe_dead_letters(listener_ref);
+    let stashing_props = StashingActor::props(probe.events_tx(), MailboxType::of_unbounded_deque());
+    let mut stashing_ref = actor_system.actor_of(stashing_props, "stashing");
+
+    let sender = ActorRef::of_mock(stashing_ref.path().with_child("sender"));
+    stashing_ref.tell_with_sender("a".to_string(), sender.clone());
+    stashing_ref.tell_with_sender("b".to_string(), sender);
+    assert_eq!(block_on(stashing_ref.graceful_stop(EVENT_TIMEOUT, None)), Ok(()));
+
+    assert_eq!(dead_letters.next_n_sorted(2), vec!["a:sender->stashing", "b:sender->stashing"]);
+    assert!(probe.drain().is_empty());
+  }
+
+  #[test]
+  fn test_stash_reports_overflow_and_unsupported_mailbox() {
+    let probe = EventProbe::new();
+    let (_actor_system, mut stashing_ref) =
+      start_stashing_system(&probe, MailboxType::of_unbounded_deque_with_stash_capacity(1));
+    stashing_ref.tell("a".to_string());
+    stashing_ref.tell("b".to_string());
+    stashing_ref.tell("init".to_string());
+
+    assert_eq!(probe.next_n(3), vec!["stash_failed:b:Stash overflow: capacity = 1", "unstashed:1", "received:a"]);
+
+    let probe = EventProbe::new();
+    let (_actor_system, mut stashing_ref) = start_stashing_system(&probe, MailboxType::of_unbounded());
+    stashing_ref.tell("a".to_string());
+    assert_eq!(probe.next(), "stash_failed:a:Stash requires a deque-based mailbox");
+  }
+}
@@ -0,0 +1,128 @@
+use crate::core::actor::actor_cell::ActorCell;
+use crate::core::dispatch::message::Message;
+use crate::mutex_lock_with_log;
+use std::future::Future;
+use tokio::task::JoinHandle;
+
+impl<Msg: Message> ActorCell<Msg> {
+  /// Runs `future` on the dispatcher's runtime as a task owned by this actor.
+  pub(crate) fn spawn_task<F>(&mut self, future: F)
+  where
+    F: Future<Output = ()> + Send + 'static, {
+    let inner = mutex_lock_with_log!(self.inner, "spawn_task");
+    let join_handle = inner.dispatcher.runtime().spawn(future);
+    Self::track_task(&mut inner.tasks.lock().unwrap(), join_handle);
+  }
+
+  /// Runs `f` on the blocking thread pool as a task owned by this actor.
+  ///
+  /// Aborting does not interrupt `f` once it has started, it only drops its result.
+  pub(crate) fn spawn_blocking<F>(&mut self, f: F)
+  where
+    F: FnOnce() + Send + 'static, {
+    let inner = mutex_lock_with_log!(self.inner, "spawn_blocking");
+    let join_handle = inner.dispatcher.runtime().spawn_blocking(f);
+    Self::track_task(&mut inner.tasks.lock().unwrap(), join_handle);
+  }
+
+  fn track_task(tasks: &mut Vec<JoinHandle<()>>, join_handle: JoinHandle<()>) {
+    tasks.retain(|task| !task.is_finished());
+    tasks.push(join_handle);
+  }
+
+  /// Aborts the tasks spawned by this actor; called when it stops or restarts.
+  pub(crate) fn abort_tasks(&mut self) {
+    let tasks = {
+      let inner = mutex_lock_with_log!(self.inner, "abort_tasks");
+      let tasks = inner.tasks.lock().unwrap().drain(..).collect::<Vec<_>>();
+      tasks
+    };
+    for task in tasks {
+      task.abort();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::core::actor::actor_context::{ActorContext, ActorContextBehavior};
+  use crate::core::actor::actor_ref::ActorRefBehavior;
+  use crate::core::actor::props::FunctionProps;
+  use crate::core::actor::supervisor_strategy::{Directive, SupervisorStrategy};
+  use crate::core::actor::test_kit::{block_on, start_system, EventProbe, SupervisingActor, EVENT_TIMEOUT};
+  use crate::core::actor::{ActorBehavior, ActorError, ActorResult};
+  use std::rc::Rc;
+  use std::sync::mpsc::Sender;
+  use std::time::Duration;
+
+  #[derive(Debug, Clone)]
+  struct TaskChildActor {
+    events_tx: Sender<String>,
+  }
+
+  impl ActorBehavior<String> for TaskChildActor {
+    fn receive(&mut self, mut ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+      let events_tx = self.events_tx.clone();
+      match msg.as_str() {
+        "tick" => ctx.spawn_task(async move {
+          loop {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            if events_tx.send("tick".to_string()).is_err() {
+              break;
+            }
+          }
+        }),
+        "compute" => ctx.spawn_blocking(move || {
+          let sum = (1..=10).sum::<u32>();
+          events_tx.send(format!("computed:{}", sum)).unwrap();
+        }),
+        _ => return Err(ActorError::ActorFailed { message: msg }),
+      }
+      Ok(())
+    }
+
+    fn post_restart(&mut self, _ctx: ActorContext<String>, _reason: ActorError) -> ActorResult<()> {
+      self.events_tx.send("post_restart".to_string()).unwrap();
+      Ok(())
+    }
+  }
+
+  fn assert_no_more_ticks(probe: &EventProbe) {
+    std::thread::sleep(Duration::from_millis(100));
+    probe.drain();
+    probe.expect_no_event(Duration::from_millis(300));
+  }
+
+  #[test]
+  fn test_spawned_tasks_are_aborted_on_restart_and_stop() {
+    let probe = EventProbe::new();
+    let events_tx = probe.events_tx();
+    let terminated = EventProbe::new();
+    let terminated_tx = terminated.events_tx();
+    let props = Rc::new(FunctionProps::of_actor(move || {
+      let events_tx = events_tx.clone();
+      let child_props = Rc::new(FunctionProps::of_actor(move || TaskChildActor {
+        events_tx: events_tx.clone(),
+      }));
+      SupervisingActor::new(
+        child_props,
+        SupervisorStrategy::of_one_for_one(None, None, |_| Directive::Restart),
+        terminated_tx.clone(),
+      )
+    }));
+    let (_actor_system, mut parent_ref) = start_system(props);
+    parent_ref.tell("tick".to_string());
+    probe.wait_for("tick");
+    parent_ref.tell("boom".to_string());
+    probe.wait_for("post_restart");
+    assert_no_more_ticks(&probe);
+
+    parent_ref.tell("compute".to_string());
+    probe.wait_for("computed:55");
+
+    parent_ref.tell("tick".to_string());
+    probe.wait_for("tick");
+    assert_eq!(block_on(parent_ref.graceful_stop(EVENT_TIMEOUT, None)), Ok(()));
+    assert_no_more_ticks(&probe);
+  }
+}
@@ -1,13 +1,18 @@
+use crate::core::actor::actor_cell::stash::StashError;
 use crate::core::actor::actor_cell::{ActorCell, ActorCellBehavior};
 use crate::core::actor::actor_ref::ActorRef;
+use crate::core::actor::actor_selection::ActorSelection;
 use crate::core::actor::props::Props;
+use crate::core::actor::Receive;
 use crate::core::dispatch::any_message::AnyMessage;
 use crate::core::dispatch::envelope::Envelope;
 use crate::core::dispatch::mailbox::dead_letter_mailbox::DeadLetterMailbox;
 use crate::core::dispatch::mailbox::mailbox::Mailbox;
 use crate::core::dispatch::message::Message;
 use crate::core::dispatch::system_message::system_message::SystemMessage;
+use std::future::Future;
 use std::rc::Rc;
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub struct ActorCellWithRef<Msg: Message> {
@@ -68,7 +73,106 @@ impl<Msg: Message> ActorCellWithRef<Msg> {
     self.actor_cell.actor_of(self.actor_ref.clone(), props)
   }
 
+  pub fn watch<U: Message>(&mut self, subject: ActorRef<U>, msg: Option<Msg>) {
+    self.actor_cell.watch(self.actor_ref.clone(), subject.to_any(false), msg);
+  }
+
+  pub fn unwatch<U: Message>(&mut self, subject: ActorRef<U>) {
+    self.actor_cell.unwatch(self.actor_ref.clone(), subject.to_any(false));
+  }
+
   pub fn actor_with_name_of<U: Message>(&mut self, props: Rc<dyn Props<U>>, name: &str) -> ActorRef<U> {
     self.actor_cell.actor_with_name_of(self.actor_ref.clone(), props, name)
   }
+
+  pub fn stash(&mut self) -> Result<(), StashError> {
+    self.actor_cell.stash()
+  }
+
+  pub fn unstash(&mut self, n: usize) -> Result<usize, StashError> {
+    self.actor_cell.unstash(self.actor_ref.clone(), n)
+  }
+
+  pub fn unstash_all(&mut self) -> Result<usize, StashError> {
+    self.actor_cell.unstash_all(self.actor_ref.clone())
+  }
+
+  pub fn r#become(&mut self, handler: Receive<Msg>, discard_old: bool) {
+    self.actor_cell.r#become(handler, discard_old)
+  }
+
+  pub fn unbecome(&mut self) {
+    self.actor_cell.unbecome()
+  }
+
+  pub fn set_receive_timeout(&mut self, timeout: Duration, msg: Msg) {
+    self.actor_cell.set_receive_timeout(self.actor_ref.clone(), timeout, msg)
+  }
+
+  pub fn cancel_receive_timeout(&mut self) {
+    self.actor_cell.cancel_receive_timeout()
+  }
+
+  pub fn get_receive_timeout(&self) -> Option<Duration> {
+    self.actor_cell.get_receive_timeout()
+  }
+
+  pub fn sender(&self) -> ActorRef<AnyMessage> {
+    self.actor_cell.sender()
+  }
+
+  pub fn reply<U: Message>(&mut self, msg: U) {
+    let self_ref = self.actor_ref.clone().to_any(true);
+    self.sender().tell_with_sender(AnyMessage::new(msg), self_ref)
+  }
+
+  pub fn forward<U: Message>(&mut self, target: &ActorRef<U>, msg: U) {
+    target.clone().tell_with_sender(msg, self.sender())
+  }
+
+  pub fn actor_selection(&self, path: &str) -> ActorSelection {
+    ActorSelection::new(self.actor_ref.clone().to_any(true), path)
+  }
+
+  pub fn pipe_to_self<T, Fut, F>(&self, future: Fut, f: F)
+  where
+    T: Send + 'static,
+    Fut: Future<Output = T> + Send + 'static,
+    F: FnOnce(T) -> Msg + Send + 'static, {
+    self.actor_cell.pipe_to_self(self.actor_ref.clone(), future, f)
+  }
+
+  pub fn spawn_task<F>(&mut self, future: F)
+  where
+    F: Future<Output = ()> + Send + 'static, {
+    self.actor_cell.spawn_task(future)
+  }
+
+  pub fn spawn_blocking<F>(&mut self, f: F)
+  where
+    F: FnOnce() + Send + 'static, {
+    self.actor_cell.spawn_blocking(f)
+  }
+
+  pub fn message_adaptor<U, F>(&self, f: F) -> ActorRef<U>
+  where
+    U: Message,
+    F: Fn(U) -> Msg + 'static, {
+    self.actor_cell.message_adapter(self.actor_ref.clone(), f)
+  }
+
+  /// Same as `message_adaptor`, spelled like `MessageAdapter`.
+  pub fn message_adapter<U, F>(&self, f: F) -> ActorRef<U>
+  where
+    U: Message,
+    F: Fn(U) -> Msg + 'static, {
+    self.message_adaptor(f)
+  }
+
+  pub(crate) fn filtering_message_adaptor<U, F>(&self, f: F) -> ActorRef<U>
+  where
+    U: Message,
+    F: Fn(U) -> Option<Msg> + 'static, {
+    self.actor_cell.filtering_message_adapter(self.actor_ref.clone(), f)
+  }
 }
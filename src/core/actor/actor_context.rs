@@ -1,11 +1,17 @@
+use std::future::Future;
 use std::rc::Rc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
+use crate::core::actor::actor_cell::stash::StashError;
 use crate::core::actor::actor_cell::ActorCell;
 use crate::core::actor::actor_cell_with_ref::ActorCellWithRef;
 use crate::core::actor::actor_ref::ActorRef;
+use crate::core::actor::actor_selection::ActorSelection;
 use crate::core::actor::props::Props;
+use crate::core::actor::ActorResult;
 use crate::core::dispatch::any_message::AnyMessage;
+use crate::core::dispatch::dispatcher::Dispatcher;
 use crate::core::dispatch::message::Message;
 
 #[derive(Debug, Clone)]
@@ -21,6 +27,17 @@ impl<Msg: Message> ActorContext<Msg> {
       actor_cell: ActorCellWithRef::new(actor_cell, self_ref),
     }
   }
+
+  pub(crate) fn dispatcher(&self) -> Dispatcher {
+    self.actor_cell.actor_cell.dispatcher()
+  }
+
+  pub(crate) fn filtering_message_adaptor<U, F>(&self, f: F) -> ActorRef<U>
+  where
+    U: Message,
+    F: Fn(U) -> Option<Msg> + 'static, {
+    self.actor_cell.filtering_message_adaptor(f)
+  }
 }
 
 impl ActorContext<AnyMessage> {
@@ -39,10 +56,41 @@ pub trait ActorContextBehavior<Msg: Message> {
   fn self_ref(&self) -> ActorRef<Msg>;
   fn spawn<U: Message>(&mut self, props: Rc<dyn Props<U>>, name: &str) -> ActorRef<U>;
   fn stop<U: Message>(&mut self, child: ActorRef<U>);
+  fn children(&self) -> Vec<ActorRef<AnyMessage>>;
+  fn child(&self, name: &str) -> Option<ActorRef<AnyMessage>>;
+  fn watch<U: Message>(&mut self, subject: &ActorRef<U>);
+  fn watch_with<U: Message>(&mut self, subject: &ActorRef<U>, msg: Msg);
+  fn unwatch<U: Message>(&mut self, subject: &ActorRef<U>);
+  fn stash(&mut self) -> Result<(), StashError>;
+  fn unstash(&mut self, n: usize) -> Result<usize, StashError>;
+  fn unstash_all(&mut self) -> Result<usize, StashError>;
+  fn r#become<F>(&mut self, handler: F, discard_old: bool)
+  where
+    F: FnMut(ActorContext<Msg>, Msg) -> ActorResult<()> + Send + 'static;
+  fn unbecome(&mut self);
   fn set_receive_timeout(&mut self, timeout: Duration, msg: Msg);
   fn cancel_receive_timeout(&mut self);
   fn get_receive_timeout(&self) -> Option<Duration>;
   fn message_adaptor<U: Message>(&self, f: impl Fn(U) -> Msg + 'static) -> ActorRef<U>;
+  /// Same as `message_adaptor`, spelled like `MessageAdapter`.
+  fn message_adapter<U: Message>(&self, f: impl Fn(U) -> Msg + 'static) -> ActorRef<U> {
+    self.message_adaptor(f)
+  }
+  fn sender(&self) -> ActorRef<AnyMessage>;
+  fn reply<U: Message>(&mut self, msg: U);
+  fn forward<U: Message>(&mut self, target: &ActorRef<U>, msg: U);
+  fn actor_selection(&self, path: &str) -> ActorSelection;
+  fn pipe_to_self<T, Fut, F>(&self, future: Fut, f: F)
+  where
+    T: Send + 'static,
+    Fut: Future<Output = T> + Send + 'static,
+    F: FnOnce(T) -> Msg + Send + 'static;
+  fn spawn_task<F>(&mut self, future: F)
+  where
+    F: Future<Output = ()> + Send + 'static;
+  fn spawn_blocking<F>(&mut self, f: F)
+  where
+    F: FnOnce() + Send + 'static;
 }
 
 impl<Msg: Message> ActorContextBehavior<Msg> for ActorContext<Msg> {
@@ -59,20 +107,98 @@ impl<Msg: Message> ActorContextBehavior<Msg> for ActorContext<Msg> {
     child.stop();
   }
 
-  fn set_receive_timeout(&mut self, _timeout: Duration, _msg: Msg) {
-    todo!()
+  fn children(&self) -> Vec<ActorRef<AnyMessage>> {
+    self.actor_cell.actor_cell.children().children()
+  }
+
+  fn child(&self, name: &str) -> Option<ActorRef<AnyMessage>> {
+    self.actor_cell.actor_cell.children().get_child_ref(name)
+  }
+
+  fn watch<U: Message>(&mut self, subject: &ActorRef<U>) {
+    self.actor_cell.watch(subject.clone(), None);
+  }
+
+  fn watch_with<U: Message>(&mut self, subject: &ActorRef<U>, msg: Msg) {
+    self.actor_cell.watch(subject.clone(), Some(msg));
+  }
+
+  fn unwatch<U: Message>(&mut self, subject: &ActorRef<U>) {
+    self.actor_cell.unwatch(subject.clone());
+  }
+
+  fn stash(&mut self) -> Result<(), StashError> {
+    self.actor_cell.stash()
+  }
+
+  fn unstash(&mut self, n: usize) -> Result<usize, StashError> {
+    self.actor_cell.unstash(n)
+  }
+
+  fn unstash_all(&mut self) -> Result<usize, StashError> {
+    self.actor_cell.unstash_all()
+  }
+
+  fn r#become<F>(&mut self, handler: F, discard_old: bool)
+  where
+    F: FnMut(ActorContext<Msg>, Msg) -> ActorResult<()> + Send + 'static, {
+    self.actor_cell.r#become(Arc::new(Mutex::new(handler)), discard_old)
+  }
+
+  fn unbecome(&mut self) {
+    self.actor_cell.unbecome()
+  }
+
+  fn set_receive_timeout(&mut self, timeout: Duration, msg: Msg) {
+    self.actor_cell.set_receive_timeout(timeout, msg);
   }
 
   fn cancel_receive_timeout(&mut self) {
-    todo!()
+    self.actor_cell.cancel_receive_timeout();
   }
 
   fn get_receive_timeout(&self) -> Option<Duration> {
-    todo!()
+    self.actor_cell.get_receive_timeout()
+  }
+
+  fn message_adaptor<U: Message>(&self, f: impl Fn(U) -> Msg + 'static) -> ActorRef<U> {
+    self.actor_cell.message_adaptor(f)
+  }
+
+  fn sender(&self) -> ActorRef<AnyMessage> {
+    self.actor_cell.sender()
+  }
+
+  fn reply<U: Message>(&mut self, msg: U) {
+    self.actor_cell.reply(msg)
   }
 
-  fn message_adaptor<U: Message>(&self, _f: impl Fn(U) -> Msg + 'static) -> ActorRef<U> {
-    todo!()
+  fn forward<U: Message>(&mut self, target: &ActorRef<U>, msg: U) {
+    self.actor_cell.forward(target, msg)
+  }
+
+  fn actor_selection(&self, path: &str) -> ActorSelection {
+    self.actor_cell.actor_selection(path)
+  }
+
+  fn pipe_to_self<T, Fut, F>(&self, future: Fut, f: F)
+  where
+    T: Send + 'static,
+    Fut: Future<Output = T> + Send + 'static,
+    F: FnOnce(T) -> Msg + Send + 'static, {
+    self.actor_cell.pipe_to_self(future, f)
+  }
+
+  fn spawn_task<F>(&mut self, future: F)
+  where
+    F: Future<Output = ()> + Send + 'static, {
+    self.actor_cell.spawn_task(future)
+  }
+
+  fn spawn_blocking<F>(&mut self, f: F)
+  where
+    F: FnOnce() + Send + 'static, {
+    self.actor_cell.spawn_blocking(f)
   }
 }
 
@@ -84,11 +210,14 @@ mod tests {
   use std::rc::Rc;
   use std::sync::{Arc, Mutex};
 
+  use std::sync::mpsc::Sender;
+
   use crate::core::actor::actor_cell::ActorCell;
-  use crate::core::actor::actor_context::ActorContext;
-  use crate::core::actor::actor_path::ActorPath;
-  use crate::core::actor::actor_ref::ActorRef;
-  use crate::core::actor::props::Props;
+  use crate::core::actor::actor_context::{ActorContext, ActorContextBehavior};
+  use crate::core::actor::actor_path::{ActorPath, ActorPathBehavior};
+  use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior, AskError};
+  use crate::core::actor::props::{FunctionProps, Props};
+  use crate::core::actor::test_kit::{start_system, EventProbe, EVENT_TIMEOUT};
   use crate::core::actor::{ActorBehavior, ActorResult};
   use crate::core::dispatch::any_message::AnyMessage;
   use crate::core::dispatch::dispatcher::Dispatcher;
@@ -144,10 +273,72 @@ mod tests {
       MailboxType::Unbounded,
       ActorRef::of_dead_letters(ActorPath::from_string("test://test")),
     );
-    let dispatcher = Dispatcher::new(Arc::new(runtime), Arc::new(Mutex::new(mailboxes)));
+    let dispatcher = Dispatcher::new(runtime.handle().clone(), Arc::new(Mutex::new(mailboxes)));
     let path = ActorPath::from_string("test://test");
     let ac: ActorCell<String> = ActorCell::new(dispatcher, path.clone(), Rc::new(TestProps {}), None);
     let ar = ActorRef::of_local(ac.clone(), path);
     let _actor_context = ActorContext::new(ac, ar);
   }
+
+  #[derive(Debug, Clone)]
+  struct EchoActor;
+
+  impl ActorBehavior<String> for EchoActor {
+    fn receive(&mut self, mut ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+      ctx.reply(format!("echo:{}", msg));
+      Ok(())
+    }
+  }
+
+  #[derive(Debug, Clone)]
+  struct RoutingActor {
+    events_tx: Sender<String>,
+    echo_ref: Option<ActorRef<String>>,
+  }
+
+  impl ActorBehavior<String> for RoutingActor {
+    fn pre_start(&mut self, mut ctx: ActorContext<String>) -> ActorResult<()> {
+      self.echo_ref = Some(ctx.spawn(Rc::new(FunctionProps::of_actor(|| EchoActor)), "echo"));
+      Ok(())
+    }
+
+    fn receive(&mut self, mut ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+      let echo_ref = self.echo_ref.as_mut().unwrap();
+      match msg.as_str() {
+        "ping" => echo_ref.tell_with_sender(msg, ctx.self_ref().to_any(true)),
+        "forward" => ctx.forward(echo_ref, msg),
+        _ => self
+          .events_tx
+          .send(format!("{}:{}", msg, sender_name(ctx.sender())))
+          .unwrap(),
+      }
+      Ok(())
+    }
+  }
+
+  fn sender_name(sender: ActorRef<AnyMessage>) -> String {
+    match sender {
+      ActorRef::NoSender => "no-sender".to_string(),
+      sender => sender.path().name().to_string(),
+    }
+  }
+
+  #[test]
+  fn test_sender_is_kept_by_reply_and_forward() {
+    let probe = EventProbe::new();
+    let events_tx = probe.events_tx();
+    let props = Rc::new(FunctionProps::of_actor(move || RoutingActor {
+      events_tx: events_tx.clone(),
+      echo_ref: None,
+    }));
+    let (_actor_system, mut routing_ref) = start_system(props);
+    routing_ref.tell("start".to_string());
+    probe.wait_for("start:no-sender");
+
+    routing_ref.tell("ping".to_string());
+    probe.wait_for("echo:ping:echo");
+
+    let reply: Result<String, AskError> = routing_ref.ask_blocking(|_| "forward".to_string(), EVENT_TIMEOUT);
+    assert_eq!(reply, Ok("echo:forward".to_string()));
+  }
 }
@@ -117,16 +117,11 @@ impl PartialOrd for ActorPath {
 impl Display for ActorPath {
   fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
     let s = match self {
-      ActorPath::Child { parent, name, uid } => {
-        let uid_str = if *uid > actor_cell::UNDEFINED_UID {
-          format!("#{}", uid.to_string())
-        } else {
-          "".to_string()
-        };
+      ActorPath::Child { parent, name, .. } => {
         if parent.is_child() || (parent.is_root() && parent.name().len() > 1) {
-          format!("{}/{}{}", parent.to_string(), name, uid_str)
+          format!("{}/{}", parent, name)
         } else {
-          format!("{}{}{}", parent.to_string(), name, uid_str)
+          format!("{}{}", parent, name)
         }
       }
       ActorPath::Root { address, name } => format!("{}{}", address.to_string(), name),
@@ -294,6 +289,15 @@ impl ActorPathBehavior for ActorPath {
 }
 
 impl ActorPath {
+  /// Returns the path with the uid of the last element, which identifies a single incarnation of the actor.
+  pub fn to_serialization_format(&self) -> String {
+    if self.uid() == actor_cell::UNDEFINED_UID {
+      self.to_string()
+    } else {
+      format!("{}#{}", self, self.uid())
+    }
+  }
+
   fn address_string_length_diff(&self, address: &Address) -> usize {
     let r = self.root();
     if r.address().host.is_some() {
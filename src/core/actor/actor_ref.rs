@@ -1,16 +1,47 @@
-use crate::core::actor::actor_cell::ActorCell;
-use crate::core::actor::actor_path::ActorPath;
+use crate::core::actor::actor_cell::{ActorCell, AutoReceivedMessage};
+use crate::core::actor::actor_path::{ActorPath, ActorPathBehavior};
 use crate::core::dispatch::any_message::AnyMessage;
+use crate::core::dispatch::mailbox::dead_letter::DeadLetter;
 
 use crate::core::dispatch::message::Message;
 use crate::core::dispatch::system_message::system_message_entry::SystemMessageEntry;
 
+use crate::core::actor::actor_ref::adapter_ref::AdapterRef;
 use crate::core::actor::actor_ref::dead_letters_ref::DeadLettersRef;
 use crate::core::actor::actor_ref::local_actor_ref::LocalActorRef;
+use crate::core::actor::actor_ref::promise_actor_ref::PromiseActorRef;
+use crate::core::actor::actor_ref_provider::temp_container::TempContainer;
 use crate::core::actor::ActorError;
+use std::future::Future;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::runtime;
+use tokio::sync::oneshot;
 
+pub mod adapter_ref;
 pub mod dead_letters_ref;
 pub mod local_actor_ref;
+pub mod promise_actor_ref;
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum GracefulStopError {
+  #[error("Actor did not terminate within {0:?}")]
+  Timeout(Duration),
+  #[error("Only local actors can be stopped gracefully")]
+  NotLocal,
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum AskError {
+  #[error("No reply within {0:?}")]
+  Timeout(Duration),
+  #[error("The recipient had already been terminated")]
+  Terminated,
+  #[error("The recipient is not an actor")]
+  InvalidRecipient,
+  #[error("The reply has an unexpected type")]
+  UnexpectedReply,
+}
 
 pub trait AnyActorRefBehavior {
   fn tell_any(&mut self, msg: AnyMessage);
@@ -32,6 +63,8 @@ pub trait ActorRefInnerBehavior<Msg: Message> {
 pub enum ActorRef<Msg: Message> {
   NoSender,
   Local(LocalActorRef<Msg>),
+  Adapter(AdapterRef<Msg>),
+  Promise(PromiseActorRef<Msg>),
   DeadLetters(DeadLettersRef),
   Mock(ActorPath),
 }
@@ -48,6 +81,8 @@ impl<Msg: Message> AnyActorRefBehavior for ActorRef<Msg> {
     match self {
       ActorRef::NoSender => {}
       ActorRef::Local(local_ref) => local_ref.tell_any(cloned_self, msg),
+      ActorRef::Adapter(adapter_ref) => adapter_ref.tell_any(msg),
+      ActorRef::Promise(promise_ref) => promise_ref.tell_any(msg),
       ActorRef::DeadLetters(dead_letters_ref) => dead_letters_ref.tell(cloned_self, msg),
       ActorRef::Mock(_) => {}
     }
@@ -59,6 +94,8 @@ impl<Msg: Message> ActorRefBehavior<Msg> for ActorRef<Msg> {
     match self {
       ActorRef::NoSender => panic!("NoSender has no path"),
       ActorRef::Local(local_ref) => local_ref.path(),
+      ActorRef::Adapter(adapter_ref) => adapter_ref.path(),
+      ActorRef::Promise(promise_ref) => promise_ref.path(),
       ActorRef::DeadLetters(dead_letters_ref) => dead_letters_ref.path(),
       ActorRef::Mock(path) => path.clone(),
     }
@@ -69,6 +106,8 @@ impl<Msg: Message> ActorRefBehavior<Msg> for ActorRef<Msg> {
     match self {
       ActorRef::NoSender => {}
       ActorRef::Local(local_ref) => local_ref.tell(cloned_self, msg),
+      ActorRef::Adapter(adapter_ref) => adapter_ref.tell(msg),
+      ActorRef::Promise(promise_ref) => promise_ref.tell(msg),
       ActorRef::DeadLetters(dead_letters_ref) => {
         let any_message = AnyMessage::new(msg);
         dead_letters_ref.tell(cloned_self.to_any(true), any_message)
@@ -87,6 +126,10 @@ impl<Msg: Message> ActorRef<Msg> {
     ActorRef::Local(LocalActorRef::new(actor_cell, path)) // , actor_cell))
   }
 
+  pub fn of_adapter(owner: LocalActorRef<AnyMessage>, path: ActorPath) -> Self {
+    ActorRef::Adapter(AdapterRef::new(owner, path))
+  }
+
   pub fn of_dead_letters(path: ActorPath) -> Self {
     ActorRef::DeadLetters(DeadLettersRef::new(path))
   }
@@ -99,11 +142,46 @@ impl<Msg: Message> ActorRef<Msg> {
     match self {
       ActorRef::NoSender => ActorRef::NoSender,
       ActorRef::Local(local_ref) => ActorRef::Local(local_ref.to_any(validate_actor)),
+      ActorRef::Adapter(adapter_ref) => ActorRef::Adapter(adapter_ref.to_any()),
+      ActorRef::Promise(promise_ref) => ActorRef::Promise(promise_ref.to_any()),
       ActorRef::DeadLetters(dead_letters_ref) => ActorRef::DeadLetters(dead_letters_ref),
       ActorRef::Mock(path) => ActorRef::Mock(path),
     }
   }
 
+  /// Like `tell`, but the recipient sees `sender` as the sender of `msg`.
+  pub fn tell_with_sender(&mut self, msg: Msg, sender: ActorRef<AnyMessage>) {
+    let cloned_self = self.clone();
+    match self {
+      ActorRef::NoSender => {}
+      ActorRef::Local(local_ref) => local_ref.tell_with_sender(cloned_self, msg, sender),
+      ActorRef::Adapter(adapter_ref) => adapter_ref.tell_with_sender(msg, sender),
+      ActorRef::Promise(promise_ref) => promise_ref.tell(msg),
+      ActorRef::DeadLetters(dead_letters_ref) => {
+        let recipient = cloned_self.to_any(true);
+        let dead_letter = DeadLetter::new(AnyMessage::new(msg), sender, recipient.clone());
+        dead_letters_ref.tell(recipient, AnyMessage::new(dead_letter))
+      }
+      ActorRef::Mock(_) => {}
+    }
+  }
+
+  /// Like `tell_with_sender`, for a message that is already wrapped in an `AnyMessage`.
+  pub fn tell_any_with_sender(&mut self, msg: AnyMessage, sender: ActorRef<AnyMessage>) {
+    let cloned_self = self.clone().to_any(true);
+    match self {
+      ActorRef::NoSender => {}
+      ActorRef::Local(local_ref) => local_ref.tell_any_with_sender(cloned_self, msg, sender),
+      ActorRef::Adapter(adapter_ref) => adapter_ref.tell_any_with_sender(msg, sender),
+      ActorRef::Promise(promise_ref) => promise_ref.tell_any(msg),
+      ActorRef::DeadLetters(dead_letters_ref) => {
+        let dead_letter = DeadLetter::new(msg, sender, cloned_self.clone());
+        dead_letters_ref.tell(cloned_self, AnyMessage::new(dead_letter))
+      }
+      ActorRef::Mock(_) => {}
+    }
+  }
+
   pub fn as_local(&self) -> Option<&LocalActorRef<Msg>> {
     match self {
       ActorRef::Local(local_ref) => Some(local_ref),
@@ -125,11 +203,44 @@ impl<Msg: Message> ActorRef<Msg> {
     }
   }
 
+  /// Creates a temporary reference under `/temp` of the actor system this reference belongs to; it is registered
+  /// there, so that it resolves, when this reference is local.
+  pub(crate) fn new_promise<R: Message>(&self) -> (PromiseActorRef<R>, oneshot::Receiver<AnyMessage>) {
+    let (mut promise_ref, reply_rx) = PromiseActorRef::new(self.path().root().clone(), self.dead_letters());
+    if let Some(temp_container) = self.temp_container() {
+      promise_ref.register(temp_container);
+    }
+    (promise_ref, reply_rx)
+  }
+
+  fn temp_container(&self) -> Option<TempContainer> {
+    match self {
+      ActorRef::Local(local_ref) => Some(local_ref.actor_cell().dispatcher().temp_container().clone()),
+      ActorRef::Adapter(adapter_ref) => adapter_ref.owner().temp_container(),
+      _ => None,
+    }
+  }
+
+  /// Returns the dead letters of the actor system this reference belongs to.
+  pub(crate) fn dead_letters(&self) -> ActorRef<AnyMessage> {
+    match self {
+      ActorRef::Local(local_ref) => local_ref.actor_cell().dead_letter_mailbox().dead_letters(),
+      ActorRef::Adapter(adapter_ref) => adapter_ref.owner().dead_letters(),
+      ActorRef::Promise(promise_ref) => promise_ref.dead_letters(),
+      ActorRef::DeadLetters(dead_letters_ref) => ActorRef::DeadLetters(dead_letters_ref.clone()),
+      ActorRef::NoSender => ActorRef::NoSender,
+      ActorRef::Mock(path) => ActorRef::DeadLetters(DeadLettersRef::new(path.root().clone().with_child("deadLetters"))),
+    }
+  }
+
   pub fn send_system_message(&mut self, message: &mut SystemMessageEntry) {
     let cloned_self = self.clone();
     match self {
       ActorRef::NoSender => {}
       ActorRef::Local(local_ref) => local_ref.send_system_message(cloned_self, message),
+      ActorRef::Adapter(_) | ActorRef::Promise(_) => {
+        log::debug!("send_system_message: ignoring {:?} sent to {}", message, self.path());
+      }
       ActorRef::DeadLetters(dead_letters_ref) => {
         dead_letters_ref.send_system_message(cloned_self.to_any(true), message)
       }
@@ -153,6 +264,93 @@ impl<Msg: Message> ActorRef<Msg> {
     }
   }
 
+  /// Stops the actor once the messages enqueued before the `PoisonPill` have been processed.
+  pub fn tell_poison_pill(&mut self) {
+    self.tell_any(AnyMessage::new(AutoReceivedMessage::PoisonPill));
+  }
+
+  /// Makes the actor fail with `ActorError::ActorKilled`, leaving the outcome to its supervisor.
+  pub fn kill(&mut self) {
+    self.tell_any(AnyMessage::new(AutoReceivedMessage::Kill));
+  }
+
+  /// Sends `stop_message` (a `PoisonPill` when `None`) and returns a future that resolves once the actor has
+  /// terminated, or fails with `GracefulStopError::Timeout` after `timeout`.
+  ///
+  /// The future has to be awaited on a tokio runtime with the time driver enabled.
+  pub fn graceful_stop(
+    &mut self,
+    timeout: Duration,
+    stop_message: Option<Msg>,
+  ) -> impl Future<Output = Result<(), GracefulStopError>> {
+    let terminated = match self.actor_cell() {
+      None => Err(GracefulStopError::NotLocal),
+      Some(actor_cell) if actor_cell.is_terminated() => Ok(None),
+      Some(actor_cell) => Ok(Some(actor_cell.terminated())),
+    };
+    if let Ok(Some(_)) = &terminated {
+      match stop_message {
+        Some(msg) => self.tell(msg),
+        None => self.tell_poison_pill(),
+      }
+    }
+    async move {
+      match terminated? {
+        None => Ok(()),
+        Some(terminated) => match tokio::time::timeout(timeout, terminated).await {
+          Ok(_) => Ok(()),
+          Err(_) => Err(GracefulStopError::Timeout(timeout)),
+        },
+      }
+    }
+  }
+
+  /// Sends the message built by `f` with a temporary reply target, and returns a future that resolves with the
+  /// first reply or fails with `AskError::Timeout` after `timeout`.
+  ///
+  /// The future has to be awaited on a tokio runtime with the time driver enabled.
+  pub fn ask<R, F>(&mut self, f: F, timeout: Duration) -> impl Future<Output = Result<R, AskError>>
+  where
+    R: Message,
+    F: FnOnce(ActorRef<R>) -> Msg, {
+    let promise = match self {
+      ActorRef::NoSender | ActorRef::Mock(_) => Err(AskError::InvalidRecipient),
+      _ if self.actor_cell().map(|actor_cell| actor_cell.is_terminated()).unwrap_or(false) => {
+        Err(AskError::Terminated)
+      }
+      _ => Ok(self.new_promise::<R>()),
+    };
+    let promise = promise.map(|(promise_ref, reply_rx)| {
+      let reply_to = ActorRef::Promise(promise_ref.clone());
+      self.tell_with_sender(f(reply_to.clone()), reply_to.to_any(false));
+      (promise_ref, reply_rx)
+    });
+    async move {
+      let (promise_ref, reply_rx) = promise?;
+      match tokio::time::timeout(timeout, reply_rx).await {
+        Ok(Ok(reply)) => {
+          let reply = reply.take::<AnyMessage>().unwrap_or(reply);
+          reply.take::<R>().map_err(|_| AskError::UnexpectedReply)
+        }
+        // The promise was dropped without a reply, e.g. because the system shut down.
+        Ok(Err(_)) => Err(AskError::Terminated),
+        Err(_) => {
+          promise_ref.expire();
+          Err(AskError::Timeout(timeout))
+        }
+      }
+    }
+  }
+
+  /// Blocking variant of `ask`; it must not be called from within a tokio runtime.
+  pub fn ask_blocking<R, F>(&mut self, f: F, timeout: Duration) -> Result<R, AskError>
+  where
+    R: Message,
+    F: FnOnce(ActorRef<R>) -> Msg, {
+    let runner = runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    runner.block_on(self.ask(f, timeout))
+  }
+
   pub fn suspend(&mut self) {
     let cloned_self = self.clone();
     match self {
@@ -175,8 +373,117 @@ impl ActorRef<AnyMessage> {
     match self {
       ActorRef::NoSender => ActorRef::NoSender,
       ActorRef::Local(local_ref) => ActorRef::Local(local_ref.to_typed(validate_actor)),
+      ActorRef::Adapter(adapter_ref) => ActorRef::Adapter(adapter_ref.to_typed()),
+      ActorRef::Promise(promise_ref) => ActorRef::Promise(promise_ref.to_typed()),
       ActorRef::DeadLetters(dead_letters_ref) => ActorRef::DeadLetters(dead_letters_ref),
       ActorRef::Mock(path) => ActorRef::Mock(path),
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::core::actor::actor_context::ActorContext;
+  use crate::core::actor::props::FunctionProps;
+  use crate::core::actor::test_kit::{block_on, start_system, EventActor, EventProbe, EVENT_TIMEOUT};
+  use crate::core::actor::{ActorBehavior, ActorResult};
+  use std::rc::Rc;
+
+  #[derive(Debug, Clone, PartialEq)]
+  enum AskMessage {
+    Ping(ActorRef<String>),
+    Ignore(ActorRef<String>),
+  }
+
+  #[derive(Debug, Clone)]
+  struct ReplyingActor;
+
+  impl ActorBehavior<AskMessage> for ReplyingActor {
+    fn receive(&mut self, _ctx: ActorContext<AskMessage>, msg: AskMessage) -> ActorResult<()> {
+      if let AskMessage::Ping(mut reply_to) = msg {
+        reply_to.tell("pong".to_string());
+      }
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn test_ask_completes_with_reply_or_times_out() {
+    let (_actor_system, mut replying_ref) = start_system(Rc::new(FunctionProps::of_actor(|| ReplyingActor)));
+
+    let reply = replying_ref.ask_blocking(AskMessage::Ping, EVENT_TIMEOUT);
+    assert_eq!(reply, Ok("pong".to_string()));
+
+    let reply = block_on(replying_ref.ask(AskMessage::Ping, EVENT_TIMEOUT));
+    assert_eq!(reply, Ok("pong".to_string()));
+
+    let timeout = Duration::from_millis(200);
+    let reply: Result<String, AskError> = replying_ref.ask_blocking(AskMessage::Ignore, timeout);
+    assert_eq!(reply, Err(AskError::Timeout(timeout)));
+
+    let mut no_sender = ActorRef::<AskMessage>::of_no_sender();
+    let reply: Result<String, AskError> = no_sender.ask_blocking(AskMessage::Ping, timeout);
+    assert_eq!(reply, Err(AskError::InvalidRecipient));
+  }
+
+  #[test]
+  fn test_graceful_stop_resolves_after_termination() {
+    let probe = EventProbe::new();
+    let child_props = EventActor::props("child", probe.events_tx(), None);
+    let parent_props = EventActor::props("parent", probe.events_tx(), Some(child_props));
+    let (_actor_system, mut parent_ref) = start_system(parent_props);
+    parent_ref.tell("start".to_string());
+    probe.wait_for("child:pre_start");
+
+    assert_eq!(block_on(parent_ref.graceful_stop(EVENT_TIMEOUT, None)), Ok(()));
+    let stopped = probe
+      .drain()
+      .into_iter()
+      .filter(|event| event.ends_with(":post_stop"))
+      .collect::<Vec<_>>();
+    assert_eq!(stopped, vec!["child:post_stop", "parent:post_stop"]);
+
+    assert_eq!(block_on(parent_ref.graceful_stop(EVENT_TIMEOUT, None)), Ok(()));
+  }
+
+  #[test]
+  fn test_graceful_stop_times_out() {
+    let probe = EventProbe::new();
+    let (_actor_system, mut parent_ref) = start_system(EventActor::props("parent", probe.events_tx(), None));
+
+    let timeout = Duration::from_millis(200);
+    let result = block_on(parent_ref.graceful_stop(timeout, Some("ignored".to_string())));
+    assert_eq!(result, Err(GracefulStopError::Timeout(timeout)));
+    probe.wait_for("parent:received:ignored");
+  }
+
+  #[test]
+  fn test_graceful_stop_can_be_retried_after_a_timeout() {
+    let probe = EventProbe::new();
+    let (_actor_system, mut parent_ref) = start_system(EventActor::props("parent", probe.events_tx(), None));
+
+    let timeout = Duration::from_millis(200);
+    let result = block_on(parent_ref.graceful_stop(timeout, Some("ignored".to_string())));
+    assert_eq!(result, Err(GracefulStopError::Timeout(timeout)));
+
+    assert_eq!(block_on(parent_ref.graceful_stop(EVENT_TIMEOUT, None)), Ok(()));
+    probe.wait_for("parent:post_stop");
+  }
+
+  #[test]
+  fn test_graceful_stop_and_when_terminate_wait_together() {
+    let probe = EventProbe::new();
+    let (actor_system, mut parent_ref) = start_system(EventActor::props("parent", probe.events_tx(), None));
+    parent_ref.tell("start".to_string());
+    probe.wait_for("parent:received:start");
+
+    let stopper = std::thread::spawn(move || {
+      std::thread::sleep(Duration::from_millis(100));
+      block_on(parent_ref.graceful_stop(EVENT_TIMEOUT, None))
+    });
+    actor_system.when_terminate();
+    assert_eq!(stopper.join().unwrap(), Ok(()));
+    probe.wait_for("parent:post_stop");
+  }
+}
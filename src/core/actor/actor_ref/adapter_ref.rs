@@ -0,0 +1,92 @@
+use crate::core::actor::actor_cell::AutoReceivedMessage;
+use crate::core::actor::actor_path::ActorPath;
+use crate::core::actor::actor_ref::local_actor_ref::LocalActorRef;
+use crate::core::actor::actor_ref::ActorRef;
+use crate::core::dispatch::any_message::AnyMessage;
+use crate::core::dispatch::message::Message;
+use std::any::TypeId;
+
+/// A reference created by `message_adaptor` that accepts `Msg` on behalf of its owner.
+///
+/// Messages are handed to the owner's mailbox as they are, and converted by the adapter function the owner has
+/// registered for `Msg` when the owner processes them.
+#[derive(Debug, Clone)]
+pub struct AdapterRef<Msg: Message> {
+  _phantom: std::marker::PhantomData<Msg>,
+  owner: Box<LocalActorRef<AnyMessage>>,
+  path: ActorPath,
+  type_id: TypeId,
+}
+
+impl<Msg: Message> PartialEq for AdapterRef<Msg> {
+  fn eq(&self, other: &Self) -> bool {
+    self.path == other.path
+  }
+}
+
+impl<Msg: Message> AdapterRef<Msg> {
+  pub fn new(owner: LocalActorRef<AnyMessage>, path: ActorPath) -> Self {
+    Self {
+      _phantom: std::marker::PhantomData,
+      owner: Box::new(owner),
+      path,
+      type_id: TypeId::of::<Msg>(),
+    }
+  }
+
+  pub fn path(&self) -> ActorPath {
+    self.path.clone()
+  }
+
+  pub fn owner(&self) -> ActorRef<AnyMessage> {
+    ActorRef::Local(*self.owner.clone())
+  }
+
+  pub fn to_any(self) -> AdapterRef<AnyMessage> {
+    AdapterRef {
+      _phantom: std::marker::PhantomData,
+      owner: self.owner,
+      path: self.path,
+      type_id: self.type_id,
+    }
+  }
+
+  pub fn tell(&mut self, message: Msg) {
+    self.tell_any(AnyMessage::new(message));
+  }
+
+  pub fn tell_with_sender(&mut self, message: Msg, sender: ActorRef<AnyMessage>) {
+    self.tell_any_with_sender(AnyMessage::new(message), sender);
+  }
+
+  pub fn tell_any(&mut self, message: AnyMessage) {
+    let owner_ref = self.owner();
+    let adapt = self.adapt(message);
+    self.owner.tell_any(owner_ref, adapt);
+  }
+
+  /// Like `tell_any`, but the owner sees `sender` as the sender of the adapted message.
+  pub fn tell_any_with_sender(&mut self, message: AnyMessage, sender: ActorRef<AnyMessage>) {
+    let owner_ref = self.owner();
+    let adapt = self.adapt(message);
+    self.owner.tell_any_with_sender(owner_ref, adapt, sender);
+  }
+
+  fn adapt(&self, message: AnyMessage) -> AnyMessage {
+    AnyMessage::new(AutoReceivedMessage::Adapt {
+      type_id: self.type_id,
+      message,
+    })
+  }
+}
+
+impl AdapterRef<AnyMessage> {
+  pub fn to_typed<Msg: Message>(self) -> AdapterRef<Msg> {
+    AdapterRef {
+      _phantom: std::marker::PhantomData,
+      owner: self.owner,
+      path: self.path,
+      type_id: self.type_id,
+    }
+  }
+}
@@ -1,16 +1,78 @@
 use crate::core::actor::actor_path::ActorPath;
-use crate::core::actor::actor_ref::{ActorRef, ActorRefInnerBehavior, AnyActorRef};
+use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior, ActorRefInnerBehavior, AnyActorRef};
 use crate::core::dispatch::any_message::AnyMessage;
+use crate::core::dispatch::mailbox::dead_letter::DeadLetter;
 use crate::core::dispatch::system_message::system_message_entry::SystemMessageEntry;
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Clone)]
 pub struct DeadLettersRef {
   path: ActorPath,
+  log_dead_letters: bool,
+  subscribers: Arc<Mutex<Vec<ActorRef<AnyMessage>>>>,
+}
+
+impl Debug for DeadLettersRef {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("DeadLettersRef")
+      .field("path", &self.path)
+      .field("log_dead_letters", &self.log_dead_letters)
+      .finish()
+  }
+}
+
+impl PartialEq for DeadLettersRef {
+  fn eq(&self, other: &Self) -> bool {
+    self.path == other.path
+  }
 }
 
 impl DeadLettersRef {
   pub fn new(path: ActorPath) -> Self {
-    Self { path }
+    Self::new_with_logging(path, true)
+  }
+
+  /// When `log_dead_letters` is false, dead letters are only logged at debug level.
+  pub fn new_with_logging(path: ActorPath, log_dead_letters: bool) -> Self {
+    Self {
+      path,
+      log_dead_letters,
+      subscribers: Arc::new(Mutex::new(Vec::new())),
+    }
+  }
+
+  /// Sends every following dead letter to `subscriber` as well, as a `DeadLetter<AnyMessage>`, until it terminates.
+  pub fn subscribe(&self, subscriber: ActorRef<AnyMessage>) {
+    self.subscribers.lock().unwrap().push(subscriber);
+  }
+
+  /// Sends `msg` to the subscribers, wrapping it in a `DeadLetter` addressed to `self_ref` if it is not one yet.
+  fn publish(&self, self_ref: ActorRef<AnyMessage>, msg: AnyMessage) {
+    let msg = msg.take::<AnyMessage>().unwrap_or(msg);
+    let dead_letter = msg
+      .take::<DeadLetter<AnyMessage>>()
+      .unwrap_or_else(|_| DeadLetter::new(msg, ActorRef::NoSender, self_ref));
+    let recipient_path = match dead_letter.recipient() {
+      ActorRef::NoSender => None,
+      recipient => Some(recipient.path()),
+    };
+    let subscribers = {
+      let mut subscribers = self.subscribers.lock().unwrap();
+      subscribers.retain(|subscriber| {
+        !subscriber
+          .actor_cell()
+          .map(|actor_cell| actor_cell.is_terminated())
+          .unwrap_or(false)
+      });
+      subscribers.clone()
+    };
+    for mut subscriber in subscribers {
+      // A dead letter that could not be delivered to a subscriber is not offered to it again.
+      if Some(subscriber.path()) != recipient_path {
+        subscriber.tell(AnyMessage::new(dead_letter.clone()));
+      }
+    }
   }
 }
 
@@ -24,7 +86,12 @@ impl ActorRefInnerBehavior<AnyMessage> for DeadLettersRef {
   }
 
   fn tell(&mut self, self_ref: ActorRef<AnyMessage>, msg: AnyMessage) {
-    log::warn!("DeadLettersRef::tell: self_ref = {:?}, msg = {:?}", self_ref, msg);
+    if self.log_dead_letters {
+      log::warn!("DeadLettersRef::tell: self_ref = {:?}, msg = {:?}", self_ref, msg);
+    } else {
+      log::debug!("DeadLettersRef::tell: self_ref = {:?}, msg = {:?}", self_ref, msg);
+    }
+    self.publish(self_ref, msg);
   }
 
   fn send_system_message(&mut self, self_ref: ActorRef<AnyMessage>, message: &mut SystemMessageEntry) {
@@ -35,3 +102,29 @@ impl ActorRefInnerBehavior<AnyMessage> for DeadLettersRef {
     );
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::core::actor::test_kit::{block_on, start_system, DeadLetterListener, EventProbe, EVENT_TIMEOUT};
+
+  #[test]
+  fn test_terminated_subscribers_are_removed() {
+    let probe = EventProbe::new();
+    let (actor_system, _) = start_system(DeadLetterListener::props(probe.events_tx()));
+    let dead_letters = EventProbe::new();
+    let mut listener_ref = actor_system.actor_of(DeadLetterListener::props(dead_letters.events_tx()), "listener");
+    actor_system.subscribe_dead_letters(listener_ref.clone());
+    let mut dead_letters_ref = actor_system.dead_letters();
+
+    dead_letters_ref.tell(AnyMessage::new("first".to_string()));
+    assert_eq!(dead_letters.next(), "first:noSender->deadLetters");
+    assert_eq!(block_on(listener_ref.graceful_stop(EVENT_TIMEOUT, None)), Ok(()));
+
+    dead_letters_ref.tell(AnyMessage::new("second".to_string()));
+    match dead_letters_ref {
+      ActorRef::DeadLetters(inner) => assert!(inner.subscribers.lock().unwrap().is_empty()),
+      _ => panic!("expected the dead letters reference"),
+    }
+  }
+}
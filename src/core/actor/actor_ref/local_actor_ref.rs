@@ -44,10 +44,22 @@ impl<Msg: Message> LocalActorRef<Msg> {
     self.actor_cell.clone().to_any(true).send_message(self_ref, msg);
   }
 
+  pub fn tell_any_with_sender(&mut self, self_ref: ActorRef<AnyMessage>, msg: AnyMessage, sender: ActorRef<AnyMessage>) {
+    self
+      .actor_cell
+      .clone()
+      .to_any(true)
+      .send_message_with_sender(self_ref, msg, sender);
+  }
+
   pub fn tell(&mut self, self_ref: ActorRef<Msg>, message: Msg) {
     self.actor_cell.send_message(self_ref, message);
   }
 
+  pub fn tell_with_sender(&mut self, self_ref: ActorRef<Msg>, message: Msg, sender: ActorRef<AnyMessage>) {
+    self.actor_cell.send_message_with_sender(self_ref, message, sender);
+  }
+
   pub fn send_system_message(&mut self, self_ref: ActorRef<Msg>, message: &mut SystemMessageEntry) {
     self.actor_cell.send_system_message(self_ref, message);
   }
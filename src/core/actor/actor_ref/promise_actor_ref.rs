@@ -0,0 +1,156 @@
+use crate::core::actor::actor_path::{ActorPath, ActorPathBehavior};
+use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior};
+use crate::core::actor::actor_ref_provider::temp_container::TempContainer;
+use crate::core::dispatch::any_message::AnyMessage;
+use crate::core::dispatch::mailbox::dead_letter::DeadLetter;
+use crate::core::dispatch::message::Message;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+
+static TEMP_NUMBER: AtomicU64 = AtomicU64::new(0);
+
+/// A temporary reference that completes an `ask` with the first message it receives.
+///
+/// It lives under `/temp` of the asked actor's system, where it resolves until it completes or expires; replies
+/// arriving after completion go to dead letters.
+#[derive(Debug, Clone)]
+pub struct PromiseActorRef<Msg: Message> {
+  _phantom: std::marker::PhantomData<Msg>,
+  path: ActorPath,
+  reply_tx: Arc<Mutex<Option<oneshot::Sender<AnyMessage>>>>,
+  dead_letters: Box<ActorRef<AnyMessage>>,
+  temp_container: Option<TempContainer>,
+}
+
+impl<Msg: Message> PartialEq for PromiseActorRef<Msg> {
+  fn eq(&self, other: &Self) -> bool {
+    self.path == other.path
+  }
+}
+
+impl<Msg: Message> PromiseActorRef<Msg> {
+  /// `dead_letters` receives the replies that arrive after the first one.
+  pub fn new(root: ActorPath, dead_letters: ActorRef<AnyMessage>) -> (Self, oneshot::Receiver<AnyMessage>) {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let temp_number = TEMP_NUMBER.fetch_add(1, Ordering::Relaxed);
+    let path = root.with_child("temp").with_child(&format!("${}", temp_number));
+    let promise_ref = Self {
+      _phantom: std::marker::PhantomData,
+      path,
+      reply_tx: Arc::new(Mutex::new(Some(reply_tx))),
+      dead_letters: Box::new(dead_letters),
+      temp_container: None,
+    };
+    (promise_ref, reply_rx)
+  }
+
+  /// Registers this reference in `temp_container` until it completes or expires.
+  pub(crate) fn register(&mut self, temp_container: TempContainer) {
+    self.temp_container = Some(temp_container.clone());
+    temp_container.add(ActorRef::Promise(self.clone().to_any()));
+  }
+
+  fn unregister(&self) {
+    if let Some(temp_container) = &self.temp_container {
+      temp_container.remove(self.path.name());
+    }
+  }
+
+  pub fn path(&self) -> ActorPath {
+    self.path.clone()
+  }
+
+  pub fn is_completed(&self) -> bool {
+    self.reply_tx.lock().unwrap().is_none()
+  }
+
+  /// Stops accepting replies, e.g. once the `ask` has timed out.
+  pub fn expire(&self) {
+    self.reply_tx.lock().unwrap().take();
+    self.unregister();
+  }
+
+  pub fn dead_letters(&self) -> ActorRef<AnyMessage> {
+    *self.dead_letters.clone()
+  }
+
+  pub fn to_any(self) -> PromiseActorRef<AnyMessage> {
+    PromiseActorRef {
+      _phantom: std::marker::PhantomData,
+      path: self.path,
+      reply_tx: self.reply_tx,
+      dead_letters: self.dead_letters,
+      temp_container: self.temp_container,
+    }
+  }
+
+  pub fn tell(&mut self, message: Msg) {
+    self.tell_any(AnyMessage::new(message));
+  }
+
+  pub fn tell_any(&mut self, message: AnyMessage) {
+    let reply_tx = self.reply_tx.lock().unwrap().take();
+    if reply_tx.is_some() {
+      self.unregister();
+    }
+    let message = match reply_tx {
+      Some(reply_tx) => match reply_tx.send(message) {
+        Ok(()) => return,
+        Err(message) => message,
+      },
+      None => message,
+    };
+    let self_ref = ActorRef::Promise(self.clone().to_any());
+    let dead_letter = DeadLetter::new(message, ActorRef::NoSender, self_ref);
+    self.dead_letters.tell(AnyMessage::new(dead_letter));
+  }
+}
+
+impl PromiseActorRef<AnyMessage> {
+  pub fn to_typed<Msg: Message>(self) -> PromiseActorRef<Msg> {
+    PromiseActorRef {
+      _phantom: std::marker::PhantomData,
+      path: self.path,
+      reply_tx: self.reply_tx,
+      dead_letters: self.dead_letters,
+      temp_container: self.temp_container,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::core::actor::actor_system::actor_system_builder::ActorSystemBuilder;
+  use crate::core::actor::actor_system::actor_system_settings::ActorSystemSettings;
+  use crate::core::actor::test_kit::{init_logger, DeadLetterListener, EventProbe, ReportingActor};
+
+  #[test]
+  fn test_late_replies_go_to_the_dead_letters_of_the_system() {
+    init_logger();
+    let probe = EventProbe::new();
+    let settings = ActorSystemSettings {
+      log_dead_letters: false,
+      ..ActorSystemSettings::default()
+    };
+    let actor_system = ActorSystemBuilder::new("test")
+      .with_guardian_props(ReportingActor::props(probe.events_tx()))
+      .with_settings(settings)
+      .build()
+      .unwrap();
+    let guardian_ref = actor_system.guardian_ref();
+    assert_eq!(guardian_ref.dead_letters(), actor_system.dead_letters());
+    let dead_letters = EventProbe::new();
+    let listener_ref = actor_system.actor_of(DeadLetterListener::props(dead_letters.events_tx()), "listener");
+    actor_system.subscribe_dead_letters(listener_ref);
+
+    let (mut promise_ref, reply_rx) =
+      PromiseActorRef::<String>::new(guardian_ref.path().root().clone(), guardian_ref.dead_letters());
+    assert_eq!(promise_ref.dead_letters(), actor_system.dead_letters());
+    promise_ref.tell("first".to_string());
+    promise_ref.tell("late".to_string());
+    assert_eq!(reply_rx.blocking_recv().unwrap().take::<String>().ok(), Some("first".to_string()));
+    assert_eq!(dead_letters.next(), format!("late:noSender->{}", promise_ref.path().name()));
+  }
+}
@@ -4,6 +4,9 @@ use crate::core::actor::address::Address;
 use crate::core::dispatch::any_message::AnyMessage;
 use std::fmt::Debug;
 
+pub mod local_actor_ref_provider;
+pub mod temp_container;
+
 pub trait ActorRefProvider: Debug {
   fn root_guardian(&self) -> ActorRef<AnyMessage>;
   fn root_guardian_at(&self, address: Address) -> ActorRef<AnyMessage>;
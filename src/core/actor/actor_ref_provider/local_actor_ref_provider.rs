@@ -0,0 +1,281 @@
+use crate::core::actor::actor_cell::ActorCell;
+use crate::core::actor::actor_context::ActorContext;
+use crate::core::actor::actor_path::{ActorPath, ActorPathBehavior};
+use crate::core::actor::actor_ref::dead_letters_ref::DeadLettersRef;
+use crate::core::actor::actor_ref::ActorRef;
+use crate::core::actor::actor_ref_provider::temp_container::TempContainer;
+use crate::core::actor::actor_ref_provider::ActorRefProvider;
+use crate::core::actor::address::Address;
+use crate::core::actor::props::{FunctionProps, Props};
+use crate::core::actor::supervisor_strategy::{Directive, SupervisorStrategy};
+use crate::core::actor::{ActorBehavior, ActorResult};
+use crate::core::dispatch::any_message::AnyMessage;
+use crate::core::dispatch::dispatcher::Dispatcher;
+use crate::core::dispatch::mailbox::dead_letter::DeadLetter;
+use crate::core::dispatch::mailbox::dead_letter_mailbox::DeadLetterMailbox;
+use crate::core::dispatch::mailbox::mailbox_type::MailboxType;
+use crate::core::dispatch::message::Message;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The guardians only supervise their children, so they accept no messages.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GuardianMessage {}
+
+/// The root guardian stops the children that fail; `/user` and `/system` supervise theirs like any other actor.
+#[derive(Debug, Clone)]
+struct GuardianActor {
+  stops_failed_children: bool,
+}
+
+impl ActorBehavior<GuardianMessage> for GuardianActor {
+  fn receive(&mut self, _ctx: ActorContext<GuardianMessage>, msg: GuardianMessage) -> ActorResult<()> {
+    match msg {}
+  }
+
+  fn supervisor_strategy(&self) -> SupervisorStrategy {
+    if self.stops_failed_children {
+      SupervisorStrategy::of_one_for_one(None, None, |_| Directive::Stop)
+    } else {
+      SupervisorStrategy::default()
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+struct Guardians {
+  root_guardian: ActorRef<GuardianMessage>,
+  guardian: ActorRef<GuardianMessage>,
+  system_guardian: ActorRef<GuardianMessage>,
+}
+
+/// Builds the actor hierarchy of a local actor system:
+///
+/// - `/` is the root guardian, the parent of everything else.
+/// - `/user` is the parent of the actors created by the user, starting with the one created from the main props.
+/// - `/system` is the parent of the actors the system creates for itself.
+/// - `/deadLetters` receives the messages that could not be delivered.
+/// - `/temp` holds temporary references such as those created by `ask`, while they wait for a reply.
+#[derive(Debug, Clone)]
+pub struct LocalActorRefProvider {
+  root_path: ActorPath,
+  dead_letters: ActorRef<AnyMessage>,
+  temp_container: TempContainer,
+  guardians: Option<Guardians>,
+}
+
+impl LocalActorRefProvider {
+  pub fn new(address: Address, log_dead_letters: bool) -> Self {
+    let root_path = ActorPath::of_root(address);
+    let dead_letters_path = root_path.clone().with_child("deadLetters");
+    let dead_letters = ActorRef::DeadLetters(DeadLettersRef::new_with_logging(dead_letters_path, log_dead_letters));
+    Self {
+      root_path,
+      dead_letters,
+      temp_container: TempContainer::default(),
+      guardians: None,
+    }
+  }
+
+  /// Creates and starts the guardians, and returns the actor created from `main_props` as `/user/main`.
+  pub fn init<Msg: Message>(
+    &mut self,
+    dispatcher: Dispatcher,
+    dead_letter_mailbox: DeadLetterMailbox,
+    main_props: Rc<dyn Props<Msg>>,
+  ) -> ActorRef<Msg> {
+    if self.guardians.is_some() {
+      panic!("LocalActorRefProvider already initialized");
+    }
+    let root_guardian_props = Rc::new(FunctionProps::new(|| {
+      Rc::new(RefCell::new(GuardianActor {
+        stops_failed_children: true,
+      }))
+    }));
+    let mut root_guardian_cell = ActorCell::new(dispatcher, self.root_path.clone(), root_guardian_props, None);
+    let mut root_guardian = ActorRef::of_local(root_guardian_cell.clone(), self.root_path.clone());
+    root_guardian_cell.initialize(
+      root_guardian.clone(),
+      MailboxType::of_unbounded(),
+      dead_letter_mailbox,
+      false,
+    );
+    root_guardian.start();
+
+    let guardian_props: Rc<dyn Props<GuardianMessage>> = Rc::new(FunctionProps::new(|| {
+      Rc::new(RefCell::new(GuardianActor {
+        stops_failed_children: false,
+      }))
+    }));
+    let guardian = root_guardian_cell.actor_with_name_of(root_guardian.clone(), guardian_props.clone(), "user");
+    let system_guardian = root_guardian_cell.actor_with_name_of(root_guardian.clone(), guardian_props, "system");
+    log::debug!("init: guardians of {} have been started", self.root_path);
+
+    let mut guardian_cell = guardian.actor_cell().unwrap();
+    let main_ref = guardian_cell.actor_with_name_of(guardian.clone(), main_props, "main");
+    self.guardians = Some(Guardians {
+      root_guardian,
+      guardian,
+      system_guardian,
+    });
+    main_ref
+  }
+
+  fn guardians(&self) -> &Guardians {
+    self.guardians.as_ref().expect("LocalActorRefProvider not initialized")
+  }
+
+  pub fn temp_path(&self) -> ActorPath {
+    self.root_path.clone().with_child("temp")
+  }
+
+  /// Returns the container the temporary references of this provider are registered in.
+  pub fn temp_container(&self) -> TempContainer {
+    self.temp_container.clone()
+  }
+
+  /// Sends every following dead letter of this provider to `subscriber` as well.
+  pub fn subscribe_dead_letters(&self, subscriber: ActorRef<DeadLetter<AnyMessage>>) {
+    if let ActorRef::DeadLetters(dead_letters) = &self.dead_letters {
+      dead_letters.subscribe(subscriber.to_any(true));
+    }
+  }
+
+  /// Looks up the actor at `path`, returning dead letters if there is none.
+  pub fn resolve_actor_ref(&self, path: &ActorPath) -> ActorRef<AnyMessage> {
+    if path.root() != &self.root_path {
+      log::debug!("resolve_actor_ref: {} is not local to {}", path, self.root_path);
+      return self.dead_letters();
+    }
+    let elements = path.elements();
+    let (first, rest) = elements.split_first().unwrap();
+    let start = match first.as_str() {
+      "" => return self.root_guardian(),
+      "deadLetters" if rest.is_empty() => return self.dead_letters(),
+      "temp" if rest.len() == 1 => return self.temp_container.get(&rest[0]).unwrap_or_else(|| self.dead_letters()),
+      "user" => self.guardian(),
+      "system" => self.system_guardian(),
+      _ => return self.dead_letters(),
+    };
+    rest
+      .iter()
+      .try_fold(start, |actor_ref, name| {
+        actor_ref.actor_cell().and_then(|cell| cell.children().get_child_ref(name))
+      })
+      .unwrap_or_else(|| self.dead_letters())
+  }
+}
+
+impl ActorRefProvider for LocalActorRefProvider {
+  fn root_guardian(&self) -> ActorRef<AnyMessage> {
+    self.guardians().root_guardian.clone().to_any(false)
+  }
+
+  fn root_guardian_at(&self, address: Address) -> ActorRef<AnyMessage> {
+    if &address == self.root_path.address() {
+      self.root_guardian()
+    } else {
+      self.dead_letters()
+    }
+  }
+
+  fn guardian(&self) -> ActorRef<AnyMessage> {
+    self.guardians().guardian.clone().to_any(false)
+  }
+
+  fn system_guardian(&self) -> ActorRef<AnyMessage> {
+    self.guardians().system_guardian.clone().to_any(false)
+  }
+
+  fn dead_letters(&self) -> ActorRef<AnyMessage> {
+    self.dead_letters.clone()
+  }
+
+  fn root_path(&self) -> ActorPath {
+    self.root_path.clone()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::core::actor::actor_context::ActorContextBehavior;
+  use crate::core::actor::actor_ref::ActorRefBehavior;
+  use crate::core::actor::test_kit::{start_system, EVENT_TIMEOUT};
+  use std::sync::mpsc::{channel, Sender};
+
+  #[derive(Debug, Clone)]
+  struct ParentActor {
+    spawned_tx: Sender<ActorRef<String>>,
+  }
+
+  impl ActorBehavior<String> for ParentActor {
+    fn pre_start(&mut self, mut ctx: ActorContext<String>) -> ActorResult<()> {
+      let child_props = Rc::new(FunctionProps::new(|| Rc::new(RefCell::new(ChildActor))));
+      let child_ref = ctx.spawn(child_props, "child");
+      self.spawned_tx.send(child_ref).unwrap();
+      Ok(())
+    }
+
+    fn receive(&mut self, _ctx: ActorContext<String>, _msg: String) -> ActorResult<()> {
+      Ok(())
+    }
+  }
+
+  #[derive(Debug, Clone)]
+  struct ChildActor;
+
+  impl ActorBehavior<String> for ChildActor {
+    fn receive(&mut self, _ctx: ActorContext<String>, _msg: String) -> ActorResult<()> {
+      Ok(())
+    }
+  }
+
+  #[test]
+  fn test_guardians_and_resolve_actor_ref() {
+    let (spawned_tx, spawned_rx) = channel();
+    let main_props = Rc::new(FunctionProps::new(move || {
+      Rc::new(RefCell::new(ParentActor {
+        spawned_tx: spawned_tx.clone(),
+      }))
+    }));
+
+    let (actor_system, main_ref) = start_system(main_props);
+    let child_ref = spawned_rx.recv_timeout(EVENT_TIMEOUT).unwrap();
+    let provider = actor_system.provider();
+
+    assert_eq!(provider.root_path().to_string(), "actuator://test/");
+    assert_eq!(provider.root_guardian().path().to_string(), "actuator://test/");
+    assert_eq!(provider.guardian().path().to_string(), "actuator://test/user");
+    assert_eq!(main_ref.path().to_string(), "actuator://test/user/main");
+    assert_eq!(provider.system_guardian().path().to_string(), "actuator://test/system");
+    assert_eq!(provider.dead_letters().path().to_string(), "actuator://test/deadLetters");
+    assert_eq!(provider.temp_path().to_string(), "actuator://test/temp");
+    assert_eq!(child_ref.path().to_string(), "actuator://test/user/main/child");
+
+    let resolved = provider.resolve_actor_ref(&child_ref.path());
+    assert_eq!(resolved.as_local().map(|local_ref| local_ref.path()), Some(child_ref.path()));
+    let resolved = provider.resolve_actor_ref(&provider.guardian().path());
+    assert_eq!(resolved, provider.guardian());
+    let resolved = provider.resolve_actor_ref(&provider.root_path());
+    assert_eq!(resolved.path(), provider.root_path());
+    let missing = main_ref.path().with_child("missing");
+    assert_eq!(provider.resolve_actor_ref(&missing), provider.dead_letters());
+    let foreign = ActorPath::of_root(Address::new("tcp", "other")).with_child("user");
+    assert_eq!(provider.resolve_actor_ref(&foreign), provider.dead_letters());
+  }
+
+  #[test]
+  fn test_resolve_actor_ref_finds_pending_temporary_references() {
+    let (actor_system, main_ref) = start_system(Rc::new(FunctionProps::new(|| Rc::new(RefCell::new(ChildActor)))));
+    let provider = actor_system.provider();
+
+    let (mut promise_ref, _reply_rx) = main_ref.new_promise::<String>();
+    assert_eq!(promise_ref.path().parent(), &provider.temp_path());
+    let resolved = provider.resolve_actor_ref(&promise_ref.path());
+    assert_eq!(resolved, ActorRef::Promise(promise_ref.clone().to_any()));
+
+    promise_ref.tell("reply".to_string());
+    assert_eq!(provider.resolve_actor_ref(&promise_ref.path()), provider.dead_letters());
+  }
+}
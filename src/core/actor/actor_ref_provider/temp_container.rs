@@ -0,0 +1,28 @@
+use crate::core::actor::actor_path::ActorPathBehavior;
+use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior};
+use crate::core::dispatch::any_message::AnyMessage;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Holds the temporary references of an actor system, such as those created by `ask`, so that `/temp/<name>`
+/// resolves to them while they wait for a reply.
+#[derive(Debug, Clone, Default)]
+pub struct TempContainer {
+  refs: Arc<Mutex<HashMap<String, ActorRef<AnyMessage>>>>,
+}
+
+impl TempContainer {
+  /// Registers `actor_ref` under the name of its path.
+  pub fn add(&self, actor_ref: ActorRef<AnyMessage>) {
+    let name = actor_ref.path().name().to_string();
+    self.refs.lock().unwrap().insert(name, actor_ref);
+  }
+
+  pub fn remove(&self, name: &str) {
+    self.refs.lock().unwrap().remove(name);
+  }
+
+  pub fn get(&self, name: &str) -> Option<ActorRef<AnyMessage>> {
+    self.refs.lock().unwrap().get(name).cloned()
+  }
+}
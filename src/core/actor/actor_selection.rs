@@ -0,0 +1,390 @@
+use crate::core::actor::actor_path::ActorPathBehavior;
+use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior};
+use crate::core::dispatch::any_message::AnyMessage;
+use crate::core::dispatch::mailbox::dead_letter::DeadLetter;
+use crate::core::dispatch::message::Message;
+use std::any::Any;
+use std::future::Future;
+use std::time::Duration;
+use thiserror::Error;
+
+/// Asks every actor it reaches to reply to the sender with an `ActorIdentity`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Identify {
+  message_id: String,
+}
+
+impl Identify {
+  pub fn new(message_id: &str) -> Self {
+    Self {
+      message_id: message_id.to_string(),
+    }
+  }
+
+  pub fn message_id(&self) -> &str {
+    &self.message_id
+  }
+}
+
+/// The reply to `Identify`; `actor_ref` is `None` when a selection matched no actor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActorIdentity {
+  message_id: String,
+  actor_ref: Option<ActorRef<AnyMessage>>,
+}
+
+impl ActorIdentity {
+  pub fn new(message_id: &str, actor_ref: Option<ActorRef<AnyMessage>>) -> Self {
+    Self {
+      message_id: message_id.to_string(),
+      actor_ref,
+    }
+  }
+
+  pub fn message_id(&self) -> &str {
+    &self.message_id
+  }
+
+  pub fn actor_ref(&self) -> Option<&ActorRef<AnyMessage>> {
+    self.actor_ref.as_ref()
+  }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ActorSelectionError {
+  #[error("No actor matches {0}")]
+  ActorNotFound(String),
+  #[error("No identity within {0:?}")]
+  Timeout(Duration),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelectionPathElement {
+  ChildName(String),
+  ChildPattern(String),
+  Parent,
+}
+
+impl SelectionPathElement {
+  fn parse(element: &str) -> Self {
+    match element {
+      ".." => SelectionPathElement::Parent,
+      _ if element.contains(['*', '?']) => SelectionPathElement::ChildPattern(element.to_string()),
+      _ => SelectionPathElement::ChildName(element.to_string()),
+    }
+  }
+
+  fn as_str(&self) -> &str {
+    match self {
+      SelectionPathElement::ChildName(name) => name,
+      SelectionPathElement::ChildPattern(pattern) => pattern,
+      SelectionPathElement::Parent => "..",
+    }
+  }
+}
+
+/// Matches `name` against a pattern in which `*` stands for any sequence of characters and `?` for any one.
+///
+/// Runs in `O(pattern.len() * name.len())` at worst: on a mismatch it only retries from the last `*`.
+pub(crate) fn matches_pattern(pattern: &[char], name: &[char]) -> bool {
+  let (mut p, mut n) = (0, 0);
+  // The position of the last `*` in the pattern and of the name character it was matched up to.
+  let mut last_star: Option<(usize, usize)> = None;
+  while n < name.len() {
+    match pattern.get(p) {
+      Some('*') => {
+        last_star = Some((p, n));
+        p += 1;
+      }
+      Some(c) if *c == '?' || *c == name[n] => {
+        p += 1;
+        n += 1;
+      }
+      _ => match last_star {
+        Some((star_p, star_n)) => {
+          last_star = Some((star_p, star_n + 1));
+          p = star_p + 1;
+          n = star_n + 1;
+        }
+        None => return false,
+      },
+    }
+  }
+  pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// A path relative to an anchor actor that is resolved against the actor tree each time it is used.
+///
+/// A path starting with `/` is resolved from the root guardian of the anchor.
+#[derive(Debug, Clone)]
+pub struct ActorSelection {
+  anchor: ActorRef<AnyMessage>,
+  path: Vec<SelectionPathElement>,
+}
+
+impl ActorSelection {
+  pub fn new(anchor: ActorRef<AnyMessage>, path: &str) -> Self {
+    let anchor = if path.starts_with('/') {
+      Self::root_of(anchor)
+    } else {
+      anchor
+    };
+    let path = path
+      .split('/')
+      .filter(|element| !element.is_empty() && *element != ".")
+      .map(SelectionPathElement::parse)
+      .collect();
+    Self { anchor, path }
+  }
+
+  fn root_of(mut actor_ref: ActorRef<AnyMessage>) -> ActorRef<AnyMessage> {
+    while let Some(parent_ref) = actor_ref.actor_cell().and_then(|actor_cell| actor_cell.parent_ref()) {
+      actor_ref = parent_ref;
+    }
+    actor_ref
+  }
+
+  pub fn anchor(&self) -> &ActorRef<AnyMessage> {
+    &self.anchor
+  }
+
+  pub fn path(&self) -> &[SelectionPathElement] {
+    &self.path
+  }
+
+  pub fn path_string(&self) -> String {
+    let elements = self.path.iter().map(SelectionPathElement::as_str).collect::<Vec<_>>();
+    format!("{}/{}", self.anchor.path(), elements.join("/"))
+  }
+
+  /// Returns the actors currently matching this selection.
+  pub fn resolve(&self) -> Vec<ActorRef<AnyMessage>> {
+    self.path.iter().fold(vec![self.anchor.clone()], |current, element| {
+      let mut matches: Vec<ActorRef<AnyMessage>> = Vec::new();
+      for actor_ref in current.iter().flat_map(|actor_ref| Self::select(actor_ref, element)) {
+        if !matches.contains(&actor_ref) {
+          matches.push(actor_ref);
+        }
+      }
+      matches
+    })
+  }
+
+  fn select(actor_ref: &ActorRef<AnyMessage>, element: &SelectionPathElement) -> Vec<ActorRef<AnyMessage>> {
+    let actor_cell = match actor_ref.actor_cell() {
+      Some(actor_cell) => actor_cell,
+      None => return Vec::new(),
+    };
+    match element {
+      SelectionPathElement::Parent => actor_cell.parent_ref().into_iter().collect(),
+      SelectionPathElement::ChildName(name) => actor_cell.children().get_child_ref(name).into_iter().collect(),
+      SelectionPathElement::ChildPattern(pattern) => {
+        let pattern = pattern.chars().collect::<Vec<_>>();
+        actor_cell
+          .children()
+          .children()
+          .into_iter()
+          .filter(|child| matches_pattern(&pattern, &child.path().name().chars().collect::<Vec<_>>()))
+          .collect()
+      }
+    }
+  }
+
+  /// Delivers `msg` to every matching actor, or to dead letters if none matches.
+  ///
+  /// An `Identify` is answered to its sender, so it has to be sent with `tell_with_sender`; the answers to one sent
+  /// from here are dropped.
+  pub fn tell<Msg: Message>(&self, msg: Msg) {
+    self.tell_with_sender(msg, ActorRef::NoSender);
+  }
+
+  /// Delivers `msg` to every matching actor with `sender` as its sender, or to dead letters if none matches.
+  ///
+  /// An `Identify` that matches no actor is answered with an empty `ActorIdentity` instead, unless there is no
+  /// sender to answer.
+  pub fn tell_with_sender<Msg: Message>(&self, msg: Msg, mut sender: ActorRef<AnyMessage>) {
+    let matches = self.resolve();
+    if matches.is_empty() {
+      match (&msg as &dyn Any).downcast_ref::<Identify>() {
+        Some(identify) if sender != ActorRef::NoSender => {
+          let identity = ActorIdentity::new(identify.message_id(), None);
+          sender.tell(AnyMessage::new(identity));
+        }
+        _ => {
+          log::debug!("tell_with_sender: {} matches no actor", self.path_string());
+          let mut dead_letters = self.anchor.dead_letters();
+          let dead_letter = DeadLetter::new(AnyMessage::new(msg), sender, dead_letters.clone());
+          dead_letters.tell(AnyMessage::new(dead_letter));
+        }
+      }
+      return;
+    }
+    for mut actor_ref in matches {
+      actor_ref.tell_with_sender(AnyMessage::new(msg.clone()), sender.clone());
+    }
+  }
+
+  /// Identifies the matching actors and resolves with the first one that replies.
+  ///
+  /// The future has to be awaited on a tokio runtime with the time driver enabled.
+  pub fn resolve_one(
+    &self,
+    timeout: Duration,
+  ) -> impl Future<Output = Result<ActorRef<AnyMessage>, ActorSelectionError>> {
+    let matches = self.resolve();
+    let promise = match matches.first() {
+      None => Err(ActorSelectionError::ActorNotFound(self.path_string())),
+      Some(first) => {
+        let (promise_ref, reply_rx) = first.new_promise::<ActorIdentity>();
+        let sender = ActorRef::Promise(promise_ref.clone().to_any());
+        for mut actor_ref in matches {
+          actor_ref.tell_with_sender(AnyMessage::new(Identify::new("resolve_one")), sender.clone());
+        }
+        Ok((promise_ref, reply_rx))
+      }
+    };
+    let path_string = self.path_string();
+    async move {
+      let (promise_ref, reply_rx) = promise?;
+      match tokio::time::timeout(timeout, reply_rx).await {
+        Ok(Ok(reply)) => reply
+          .take::<ActorIdentity>()
+          .ok()
+          .and_then(|identity| identity.actor_ref)
+          .ok_or(ActorSelectionError::ActorNotFound(path_string)),
+        Ok(Err(_)) | Err(_) => {
+          promise_ref.expire();
+          Err(ActorSelectionError::Timeout(timeout))
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::core::actor::actor_context::{ActorContext, ActorContextBehavior};
+  use crate::core::actor::actor_ref::AskError;
+  use crate::core::actor::props::FunctionProps;
+  use crate::core::actor::test_kit::{
+    block_on, start_system, DeadLetterListener, EventProbe, ReportingActor, SpawningActor, EVENT_TIMEOUT,
+  };
+  use crate::core::actor::{ActorBehavior, ActorResult};
+  use std::rc::Rc;
+  use std::sync::mpsc::Sender;
+
+  /// Greets the sibling named in `greet:<sibling>` and reports every other message.
+  #[derive(Debug, Clone)]
+  struct OrderActor {
+    events_tx: Sender<String>,
+  }
+
+  impl ActorBehavior<String> for OrderActor {
+    fn receive(&mut self, ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+      let name = ctx.self_ref().path().name().to_string();
+      match msg.strip_prefix("greet:") {
+        Some(sibling) => ctx
+          .actor_selection(&format!("../{}", sibling))
+          .tell(format!("hello from {}", name)),
+        None => self.events_tx.send(format!("{}:{}", name, msg)).unwrap(),
+      }
+      Ok(())
+    }
+  }
+
+  fn matches(pattern: &str, name: &str) -> bool {
+    matches_pattern(
+      &pattern.chars().collect::<Vec<_>>(),
+      &name.chars().collect::<Vec<_>>(),
+    )
+  }
+
+  #[test]
+  fn test_matches_pattern() {
+    assert!(matches("*", "orders"));
+    assert!(matches("*", ""));
+    assert!(matches("order-*", "order-1"));
+    assert!(matches("order-?", "order-1"));
+    assert!(!matches("order-?", "order-12"));
+    assert!(matches("*-1?", "order-12"));
+    assert!(!matches("order-*", "invoice-1"));
+    assert!(matches("a*b*c", "axxbyyc"));
+    assert!(!matches("a*b*c", "axxbyy"));
+    assert!(matches("*?", "a"));
+    assert!(!matches("?", ""));
+    assert!(matches("a**", "a"));
+  }
+
+  #[test]
+  fn test_matches_pattern_does_not_backtrack_exponentially() {
+    let name = "a".repeat(10_000);
+    assert!(!matches("*a*a*a*a*a*a*a*a*b", &name));
+    assert!(matches("*a*a*a*a*a*a*a*a*", &name));
+  }
+
+  #[test]
+  fn test_parse_path() {
+    let selection = ActorSelection::new(ActorRef::NoSender, "../orders/./order-*/x?");
+    assert_eq!(
+      selection.path(),
+      &[
+        SelectionPathElement::Parent,
+        SelectionPathElement::ChildName("orders".to_string()),
+        SelectionPathElement::ChildPattern("order-*".to_string()),
+        SelectionPathElement::ChildPattern("x?".to_string()),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_actor_selection_delivers_to_matches_and_identifies_them() {
+    let probe = EventProbe::new();
+    let (actor_system, _) = start_system(ReportingActor::props(probe.events_tx()));
+    let events_tx = probe.events_tx();
+    let order_props = Rc::new(FunctionProps::of_actor(move || OrderActor {
+      events_tx: events_tx.clone(),
+    }));
+    let mut orders_ref = actor_system.actor_of(SpawningActor::props(order_props), "orders");
+    for name in ["order-1", "order-2", "invoice-1"] {
+      let reply: Result<String, AskError> = orders_ref.ask_blocking(|_| name.to_string(), EVENT_TIMEOUT);
+      assert_eq!(reply, Ok(format!("spawned:{}", name)));
+    }
+
+    actor_system.actor_selection("/user/orders/order-*").tell("created".to_string());
+    assert_eq!(probe.next_n_sorted(2), vec!["order-1:created", "order-2:created"]);
+    actor_system.actor_selection("orders/*-?").tell("paid".to_string());
+    assert_eq!(probe.next_n_sorted(3), vec!["invoice-1:paid", "order-1:paid", "order-2:paid"]);
+    actor_system.actor_selection("/user/orders/order-1").tell("greet:invoice-1".to_string());
+    assert_eq!(probe.next(), "invoice-1:hello from order-1");
+
+    let selection = actor_system.actor_selection("/user/orders/order-2");
+    let mut resolved = block_on(selection.resolve_one(EVENT_TIMEOUT)).unwrap();
+    let order_2 = orders_ref.actor_cell().unwrap().children().get_child_ref("order-2").unwrap();
+    assert_eq!(resolved.path(), order_2.path());
+    assert_ne!(resolved.path().uid(), 0);
+    assert!(resolved.path().to_serialization_format().ends_with(&format!("#{}", resolved.path().uid())));
+    resolved.tell(AnyMessage::new("shipped".to_string()));
+    assert_eq!(probe.next(), "order-2:shipped");
+
+    let selection = actor_system.actor_selection("/user/orders/missing-*");
+    let result = block_on(selection.resolve_one(EVENT_TIMEOUT));
+    assert!(matches!(result, Err(ActorSelectionError::ActorNotFound(_))));
+  }
+
+  #[test]
+  fn test_actor_selection_sends_unmatched_messages_to_dead_letters() {
+    let probe = EventProbe::new();
+    let (actor_system, main_ref) = start_system(ReportingActor::props(probe.events_tx()));
+    let dead_letters = EventProbe::new();
+    let listener_ref = actor_system.actor_of(DeadLetterListener::props(dead_letters.events_tx()), "listener");
+    actor_system.subscribe_dead_letters(listener_ref);
+
+    actor_system.actor_selection("/user/missing-*").tell("lost".to_string());
+    assert_eq!(dead_letters.next(), "lost:noSender->deadLetters");
+    let sender = ActorRef::of_mock(main_ref.path().with_child("sender"));
+    actor_system.actor_selection("missing").tell_with_sender("also lost".to_string(), sender);
+    assert_eq!(dead_letters.next(), "also lost:sender->deadLetters");
+    assert!(probe.drain().is_empty());
+  }
+}
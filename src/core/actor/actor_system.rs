@@ -1,20 +1,33 @@
-use crate::core::actor::actor_cell::ActorCell;
-use crate::core::actor::actor_path::{ActorPath, ActorPathBehavior};
+use crate::core::actor::actor_path::ActorPath;
 use crate::core::actor::actor_ref::ActorRef;
+use crate::core::actor::actor_ref_provider::local_actor_ref_provider::LocalActorRefProvider;
+use crate::core::actor::actor_ref_provider::ActorRefProvider;
+use crate::core::actor::actor_selection::ActorSelection;
+use crate::core::actor::actor_system::actor_system_settings::ActorSystemSettings;
+use crate::core::actor::children_refs::ActorCreationError;
 use crate::core::actor::address::Address;
 use crate::core::actor::props::Props;
 use crate::core::dispatch::any_message::AnyMessage;
 use crate::core::dispatch::dispatcher::Dispatcher;
-use crate::core::dispatch::mailbox::mailbox_type::MailboxType;
+use crate::core::dispatch::mailbox::dead_letter::DeadLetter;
 use crate::core::dispatch::mailboxes::Mailboxes;
 use crate::core::dispatch::message::Message;
 
-use crate::core::actor::children_refs::ChildrenRefs;
+use crate::core::actor::coordinated_shutdown::{
+  CoordinatedShutdown, CoordinatedShutdownError, ShutdownReason, PHASE_ACTOR_SYSTEM_TERMINATE,
+};
+use crate::core::actor::deployer::Deployer;
 use std::fmt::Debug;
+use std::future::Future;
 use std::rc::Rc;
 use std::sync::{Arc, Mutex, RwLock};
 use std::time::{Duration, Instant};
-use tokio::runtime::Runtime;
+use tokio::runtime;
+use tokio::runtime::{Handle, Runtime};
+use tokio::task::JoinHandle;
+
+pub mod actor_system_builder;
+pub mod actor_system_settings;
 
 pub trait ActorSystemBehavior: Debug {
   fn address(&self) -> Address;
@@ -35,13 +48,15 @@ pub struct ActorSystemInner<Msg: Message> {
   address: Address,
   name: String,
   start_time: Instant,
-  runtime: Arc<Runtime>,
-  root_ref: Option<ActorRef<Msg>>,
-  dead_letters: Option<ActorRef<AnyMessage>>,
+  runtime: Option<Arc<Runtime>>,
+  runtime_handle: Handle,
+  provider: LocalActorRefProvider,
+  guardian_ref: Option<ActorRef<Msg>>,
   dispatcher: Option<Dispatcher>,
   mailboxes: Option<Arc<Mutex<Mailboxes>>>,
-  children: ChildrenRefs,
   main_props: Option<Rc<dyn Props<Msg>>>,
+  coordinated_shutdown: CoordinatedShutdown,
+  settings: ActorSystemSettings,
 }
 
 pub struct ActorSystem<Msg: Message> {
@@ -50,39 +65,134 @@ pub struct ActorSystem<Msg: Message> {
 
 impl<Msg: Message> ActorSystem<Msg> {
   pub fn new(runtime: Runtime, address: Address, name: &str, main_props: Rc<dyn Props<Msg>>) -> Self {
+    let runtime_handle = runtime.handle().clone();
+    Self::new_with_settings(
+      Some(runtime),
+      runtime_handle,
+      address,
+      name,
+      main_props,
+      ActorSystemSettings::default(),
+    )
+  }
+
+  /// Creates an actor system whose actors run on `runtime_handle`; `runtime` is the runtime it owns, if any.
+  pub(crate) fn new_with_settings(
+    runtime: Option<Runtime>,
+    runtime_handle: Handle,
+    address: Address,
+    name: &str,
+    main_props: Rc<dyn Props<Msg>>,
+    settings: ActorSystemSettings,
+  ) -> Self {
     Self {
       inner: Arc::new(RwLock::new(ActorSystemInner {
-        address,
+        address: address.clone(),
         name: name.to_string(),
         start_time: Instant::now(),
-        runtime: Arc::new(runtime),
-        root_ref: None,
-        dead_letters: None,
+        runtime: runtime.map(Arc::new),
+        runtime_handle,
+        provider: LocalActorRefProvider::new(address, settings.log_dead_letters),
+        guardian_ref: None,
         dispatcher: None,
         mailboxes: None,
-        children: ChildrenRefs::new(),
         main_props: Some(main_props),
+        coordinated_shutdown: CoordinatedShutdown::default(),
+        settings,
       })),
     }
   }
 
+  pub fn settings(&self) -> ActorSystemSettings {
+    let inner = self.inner.read().unwrap();
+    inner.settings.clone()
+  }
+
+  pub fn provider(&self) -> LocalActorRefProvider {
+    let inner = self.inner.read().unwrap();
+    inner.provider.clone()
+  }
+
+  pub fn dead_letters(&self) -> ActorRef<AnyMessage> {
+    let inner = self.inner.read().unwrap();
+    inner.provider.dead_letters()
+  }
+
+  /// Sends every following dead letter of this system to `subscriber` as well.
+  pub fn subscribe_dead_letters(&self, subscriber: ActorRef<DeadLetter<AnyMessage>>) {
+    let inner = self.inner.read().unwrap();
+    inner.provider.subscribe_dead_letters(subscriber);
+  }
+
+  pub fn coordinated_shutdown(&self) -> CoordinatedShutdown {
+    let inner = self.inner.read().unwrap();
+    inner.coordinated_shutdown.clone()
+  }
+
+  /// Runs the coordinated shutdown and blocks until its last phase, which stops the root actor, has finished.
+  ///
+  /// Must not be called from within a tokio runtime; use `terminate_async` there.
+  pub fn terminate(&self) -> Result<(), CoordinatedShutdownError> {
+    let runner = runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    runner.block_on(self.terminate_async())
+  }
+
+  /// Runs the coordinated shutdown and resolves once its last phase, which stops the root actor, has finished.
+  pub async fn terminate_async(&self) -> Result<(), CoordinatedShutdownError> {
+    let result = self
+      .coordinated_shutdown()
+      .run(ShutdownReason::ActorSystemTerminate)
+      .await;
+    self.release();
+    result
+  }
+
+  /// Runs the coordinated shutdown on the runtime of this actor system as soon as the process receives SIGTERM
+  /// or SIGINT.
+  pub fn run_coordinated_shutdown_on_signals(&self) -> JoinHandle<()> {
+    let inner = self.inner.read().unwrap();
+    inner.coordinated_shutdown.run_on_signals(&inner.runtime_handle)
+  }
+
+  /// Runs the coordinated shutdown on the runtime of this actor system once `trigger` resolves.
+  pub fn run_coordinated_shutdown_when<T>(&self, trigger: T) -> JoinHandle<()>
+  where
+    T: Future<Output = String> + Send + 'static, {
+    let inner = self.inner.read().unwrap();
+    inner.coordinated_shutdown.run_when(&inner.runtime_handle, trigger)
+  }
+
+  /// Blocks until `/user/main` has stopped, then runs the coordinated shutdown unless it has already started, and
+  /// blocks until its last phase has stopped the root actor as well.
+  ///
+  /// Must not be called from within a tokio runtime.
   pub fn when_terminate(&self) {
-    {
-      let inner = self.inner.write().unwrap();
-      let root_ref = inner.root_ref.as_ref().unwrap();
-      let actor_cell_opt = root_ref.actor_cell();
-      let actor_cell = actor_cell_opt.as_ref().unwrap();
-      actor_cell.when_terminate();
-    }
+    let (main_cell, root_guardian_cell) = {
+      let inner = self.inner.read().unwrap();
+      let main_ref = inner.guardian_ref.clone().expect("ActorSystem not initialized");
+      (main_ref.actor_cell().unwrap(), inner.provider.root_guardian().actor_cell().unwrap())
+    };
+    let coordinated_shutdown = self.coordinated_shutdown();
+    let runner = runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    runner.block_on(async {
+      main_cell.terminated().await;
+      match coordinated_shutdown.run(ShutdownReason::ActorSystemTerminate).await {
+        Ok(()) | Err(CoordinatedShutdownError::AlreadyStarted) => {}
+        Err(error) => log::error!("when_terminate: {}", error),
+      }
+      root_guardian_cell.terminated().await;
+    });
+    log::info!("when_terminate: terminated");
+    self.release();
+  }
+
+  fn release(&self) {
     {
       let mut inner = self.inner.write().unwrap();
-      let dead_letters = inner.dead_letters.take();
-      drop(dead_letters);
       let mailboxes = inner.mailboxes.take();
       drop(mailboxes);
-      inner.children = ChildrenRefs::new();
-      let root_ref = inner.root_ref.take();
-      drop(root_ref);
+      let guardian_ref = inner.guardian_ref.take();
+      drop(guardian_ref);
       let main_props = inner.main_props.take();
       drop(main_props);
     }
@@ -90,37 +200,104 @@ impl<Msg: Message> ActorSystem<Msg> {
 
   pub fn initialize(&mut self) -> ActorRef<Msg> {
     let mut inner = self.inner.write().unwrap();
-    let main_path = ActorPath::of_root_with_name(inner.address.clone(), &inner.name);
-
-    let dead_letters_path = main_path.clone().with_child("dead-letters");
-    let dead_letters_ref = ActorRef::of_dead_letters(dead_letters_path.clone());
+    let dead_letters_ref = inner.provider.dead_letters();
+    let settings = inner.settings.clone();
     let mailboxes = Arc::new(Mutex::new(Mailboxes::new(
-      MailboxType::of_unbounded(),
+      settings.default_mailbox_type,
       dead_letters_ref.clone(),
     )));
 
-    let dispatcher = Dispatcher::new(inner.runtime.clone(), mailboxes.clone());
+    let dispatcher = Dispatcher::new_with_settings(
+      inner.runtime_handle.clone(),
+      mailboxes.clone(),
+      settings.throughput,
+      settings.throughput_deadline,
+      settings.scheduler_tick_duration,
+    )
+    .with_deployer(Deployer::new(settings.deployments))
+    .with_temp_container(inner.provider.temp_container())
+    .with_dispatchers(settings.dispatchers);
     inner.dispatcher = Some(dispatcher.clone());
-    inner.dead_letters = Some(dead_letters_ref.clone());
     inner.mailboxes = Some(mailboxes.clone());
 
-    let mut main_actor_cell = ActorCell::new(
-      dispatcher.clone(),
-      main_path.clone(),
-      inner.main_props.as_ref().unwrap().clone(),
-      None,
-    );
-    let main_actor_ref = ActorRef::of_local(main_actor_cell.clone(), main_path.clone());
     let dead_letter_mailbox = mailboxes.lock().unwrap().dead_letter_mailbox();
+    let main_props = inner.main_props.as_ref().unwrap().clone();
+    let guardian_ref = inner.provider.init(dispatcher, dead_letter_mailbox, main_props);
+    inner.guardian_ref = Some(guardian_ref.clone());
+
+    let coordinated_shutdown = inner.coordinated_shutdown.clone();
+    let timeout = coordinated_shutdown.phase_timeout(PHASE_ACTOR_SYSTEM_TERMINATE).unwrap();
+    let mut root_guardian = inner.provider.root_guardian();
+    coordinated_shutdown
+      .add_task(PHASE_ACTOR_SYSTEM_TERMINATE, "terminate-root", move || {
+        let stopped = root_guardian.graceful_stop(timeout, None);
+        async move {
+          if let Err(error) = stopped.await {
+            log::error!("terminate-root: {}", error);
+          }
+        }
+      })
+      .unwrap();
+    guardian_ref
+  }
 
-    main_actor_cell.initialize(
-      main_actor_ref.clone(),
-      MailboxType::of_unbounded(),
-      dead_letter_mailbox,
-      false,
-    );
-    inner.root_ref = Some(main_actor_ref.clone());
-    inner.root_ref.as_ref().unwrap().clone()
+  /// Returns `/user/main`, the actor created from the main props.
+  pub fn guardian_ref(&self) -> ActorRef<Msg> {
+    let inner = self.inner.read().unwrap();
+    inner.guardian_ref.clone().expect("ActorSystem not initialized")
+  }
+
+  /// Creates a top-level actor named `name` under the user guardian.
+  ///
+  /// Panics if the actor cannot be created; see `try_actor_of`.
+  pub fn actor_of<U: Message>(&self, props: Rc<dyn Props<U>>, name: &str) -> ActorRef<U> {
+    self
+      .try_actor_of(props, name)
+      .unwrap_or_else(|error| panic!("{}", error))
+  }
+
+  /// Creates a top-level actor named `name` under the user guardian, or fails if `name` is not a valid path
+  /// element or is already taken, or if the dispatcher the actor is configured with does not exist.
+  pub fn try_actor_of<U: Message>(
+    &self,
+    props: Rc<dyn Props<U>>,
+    name: &str,
+  ) -> Result<ActorRef<U>, ActorCreationError> {
+    let guardian_ref = self.provider().guardian();
+    let mut guardian_cell = guardian_ref.actor_cell().unwrap();
+    guardian_cell.try_actor_with_name_of(guardian_ref, props, name)
+  }
+
+  /// Creates a top-level actor with a generated name under the user guardian.
+  ///
+  /// Panics if the actor cannot be created; see `try_actor_of_anonymous`.
+  pub fn actor_of_anonymous<U: Message>(&self, props: Rc<dyn Props<U>>) -> ActorRef<U> {
+    self
+      .try_actor_of_anonymous(props)
+      .unwrap_or_else(|error| panic!("{}", error))
+  }
+
+  /// Creates a top-level actor with a generated name under the user guardian, or fails if the dispatcher the actor
+  /// is configured with does not exist.
+  pub fn try_actor_of_anonymous<U: Message>(&self, props: Rc<dyn Props<U>>) -> Result<ActorRef<U>, ActorCreationError> {
+    let guardian_ref = self.provider().guardian();
+    let mut guardian_cell = guardian_ref.actor_cell().unwrap();
+    guardian_cell.try_actor_of(guardian_ref, props)
+  }
+
+  /// Stops `actor_ref` asynchronously, after the message it is currently processing.
+  pub fn stop<U: Message>(&self, mut actor_ref: ActorRef<U>) {
+    actor_ref.stop();
+  }
+
+  /// Selects actors by a path such as `/user/orders/*`; a relative path is resolved from the user guardian.
+  pub fn actor_selection(&self, path: &str) -> ActorSelection {
+    let anchor = if path.starts_with('/') {
+      self.provider().root_guardian()
+    } else {
+      self.provider().guardian()
+    };
+    ActorSelection::new(anchor, path)
   }
 
   pub fn join(&self) {
@@ -133,12 +310,14 @@ impl<Msg: Message> ActorSystem<Msg> {
 mod test {
   use super::*;
   use crate::core::actor::actor_context::{ActorContext, ActorContextBehavior};
-  use crate::core::actor::actor_ref::ActorRefBehavior;
-  use crate::core::actor::props::FunctionProps;
+  use crate::core::actor::actor_path::ActorPathBehavior;
+  use crate::core::actor::actor_ref::{ActorRefBehavior, AskError};
+  use crate::core::actor::children_refs::InvalidActorNameError;
+  use crate::core::actor::props::{FunctionProps, PropsSettingsBehavior};
+  use crate::core::actor::test_kit::{init_logger, start_system, EventProbe, ReportingActor, EVENT_TIMEOUT};
   use crate::core::actor::{ActorBehavior, ActorResult};
 
   use std::cell::RefCell;
-  use std::env;
   use tokio::runtime;
 
   #[derive(Debug, Clone)]
@@ -234,9 +413,14 @@ mod test {
     }
   }
 
-  fn init_logger() {
-    let _ = env::set_var("RUST_LOG", "debug");
-    let _ = env_logger::builder().is_test(true).try_init();
+  #[derive(Debug, Clone)]
+  struct EchoActor;
+
+  impl ActorBehavior<String> for EchoActor {
+    fn receive(&mut self, mut ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+      ctx.reply(format!("echo:{}", msg));
+      Ok(())
+    }
   }
 
   #[test]
@@ -256,4 +440,69 @@ mod test {
     // actor_system.when_terminate();
     actor_system.join();
   }
+
+  #[test]
+  fn test_actor_of_creates_top_level_actors_under_user_guardian() {
+    let probe = EventProbe::new();
+    let (actor_system, _) = start_system(ReportingActor::props(probe.events_tx()));
+    let echo_props: Rc<dyn Props<String>> = Rc::new(FunctionProps::of_actor(|| EchoActor));
+    let mut service_a = actor_system.actor_of(echo_props.clone(), "service-a");
+    let mut anonymous = actor_system.actor_of_anonymous(echo_props);
+    assert_eq!(service_a.path().to_string(), "actuator://test/user/service-a");
+    assert_eq!(anonymous.path().parent(), &actor_system.provider().guardian().path());
+
+    let reply: Result<String, AskError> = service_a.ask_blocking(|_| "a".to_string(), EVENT_TIMEOUT);
+    assert_eq!(reply, Ok("echo:a".to_string()));
+    let reply: Result<String, AskError> = anonymous.ask_blocking(|_| "b".to_string(), EVENT_TIMEOUT);
+    assert_eq!(reply, Ok("echo:b".to_string()));
+
+    actor_system.stop(service_a.clone());
+    let provider = actor_system.provider();
+    let deadline = Instant::now() + EVENT_TIMEOUT;
+    while provider.resolve_actor_ref(&service_a.path()) != provider.dead_letters() {
+      assert!(Instant::now() < deadline, "service-a was not stopped");
+      std::thread::sleep(Duration::from_millis(10));
+    }
+    assert!(service_a.actor_cell().unwrap().is_terminated());
+    assert_eq!(provider.resolve_actor_ref(&anonymous.path()).path(), anonymous.path());
+  }
+
+  #[test]
+  fn test_try_actor_of_rejects_invalid_and_duplicate_names() {
+    let probe = EventProbe::new();
+    let (actor_system, _) = start_system(ReportingActor::props(probe.events_tx()));
+    let echo_props: Rc<dyn Props<String>> = Rc::new(FunctionProps::of_actor(|| EchoActor));
+    let mut service = actor_system.try_actor_of(echo_props.clone(), "service").unwrap();
+    assert_eq!(
+      actor_system.try_actor_of(echo_props.clone(), "service").err(),
+      Some(InvalidActorNameError::NotUnique("service".to_string()).into())
+    );
+    assert_eq!(
+      actor_system.try_actor_of(echo_props.clone(), "").err(),
+      Some(InvalidActorNameError::Empty.into())
+    );
+    assert_eq!(
+      actor_system.try_actor_of(echo_props.clone(), "$service").err(),
+      Some(InvalidActorNameError::InvalidPathElement("$service".to_string()).into())
+    );
+
+    // The name is free again after a failed creation.
+    let misconfigured_props = Rc::new(FunctionProps::of_actor(|| EchoActor).with_dispatcher("missing"));
+    assert!(matches!(
+      actor_system.try_actor_of_anonymous(misconfigured_props.clone()),
+      Err(ActorCreationError::UnknownDispatcher { .. })
+    ));
+    assert_eq!(
+      actor_system.try_actor_of(misconfigured_props, "other").err(),
+      Some(ActorCreationError::UnknownDispatcher {
+        dispatcher: "missing".to_string(),
+        path: "actuator://test/user/other".to_string(),
+      })
+    );
+    assert!(actor_system.try_actor_of(echo_props, "other").is_ok());
+
+    let reply: Result<String, AskError> = service.ask_blocking(|_| "a".to_string(), EVENT_TIMEOUT);
+    assert_eq!(reply, Ok("echo:a".to_string()));
+  }
+
 }
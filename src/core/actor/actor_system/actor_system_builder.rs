@@ -0,0 +1,284 @@
+use crate::core::actor::actor_system::actor_system_settings::ActorSystemSettings;
+use crate::core::actor::actor_system::ActorSystem;
+use crate::core::actor::address::Address;
+use crate::core::actor::props::Props;
+use crate::core::dispatch::mailbox::mailbox_type::MailboxType;
+use crate::core::dispatch::message::Message;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::rc::Rc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::runtime;
+use tokio::runtime::{Handle, Runtime};
+
+static SYSTEM_NAME_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[a-zA-Z0-9][a-zA-Z0-9_-]*$").unwrap());
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ActorSystemBuildError {
+  #[error("Invalid actor system name [{0}]: it must start with an ASCII letter or digit followed by letters, digits, '-' or '_'")]
+  InvalidName(String),
+  #[error("Guardian props are not set")]
+  GuardianPropsNotSet,
+  #[error("Throughput must be at least 1")]
+  InvalidThroughput,
+  #[error("Scheduler tick must be at least 1ms: {0:?}")]
+  InvalidSchedulerTick(Duration),
+  #[error("Unknown dispatcher [{dispatcher}] for {path}")]
+  UnknownDispatcher { dispatcher: String, path: String },
+  #[error("Failed to create the runtime: {0}")]
+  RuntimeCreationFailed(String),
+}
+
+/// Collects the settings of an actor system and builds a started one.
+///
+/// Unless a runtime or a runtime handle is given, `build` creates a multi-threaded runtime that the system owns.
+/// The address defaults to `actuator://<name>`.
+#[derive(Debug)]
+pub struct ActorSystemBuilder<Msg: Message> {
+  name: String,
+  address: Option<Address>,
+  runtime: Option<Runtime>,
+  runtime_handle: Option<Handle>,
+  settings: ActorSystemSettings,
+  guardian_props: Option<Rc<dyn Props<Msg>>>,
+}
+
+impl<Msg: Message> ActorSystemBuilder<Msg> {
+  pub const DEFAULT_PROTOCOL: &'static str = "actuator";
+
+  pub fn new(name: &str) -> Self {
+    Self {
+      name: name.to_string(),
+      address: None,
+      runtime: None,
+      runtime_handle: None,
+      settings: ActorSystemSettings::default(),
+      guardian_props: None,
+    }
+  }
+
+  pub fn with_address(mut self, address: Address) -> Self {
+    self.address = Some(address);
+    self
+  }
+
+  /// Runs the actors on `runtime`, which the system takes ownership of.
+  pub fn with_runtime(mut self, runtime: Runtime) -> Self {
+    self.runtime = Some(runtime);
+    self.runtime_handle = None;
+    self
+  }
+
+  /// Runs the actors on the runtime behind `runtime_handle`, which has to outlive the system.
+  pub fn with_runtime_handle(mut self, runtime_handle: Handle) -> Self {
+    self.runtime_handle = Some(runtime_handle);
+    self.runtime = None;
+    self
+  }
+
+  pub fn with_settings(mut self, settings: ActorSystemSettings) -> Self {
+    self.settings = settings;
+    self
+  }
+
+  pub fn with_default_mailbox_type(mut self, mailbox_type: MailboxType) -> Self {
+    self.settings.default_mailbox_type = mailbox_type;
+    self
+  }
+
+  pub fn with_throughput(mut self, throughput: usize) -> Self {
+    self.settings.throughput = throughput;
+    self
+  }
+
+  pub fn with_scheduler_tick_duration(mut self, scheduler_tick_duration: Duration) -> Self {
+    self.settings.scheduler_tick_duration = scheduler_tick_duration;
+    self
+  }
+
+  /// Sets the props of `/user/main`, the first actor created under the user guardian.
+  pub fn with_guardian_props(mut self, guardian_props: Rc<dyn Props<Msg>>) -> Self {
+    self.guardian_props = Some(guardian_props);
+    self
+  }
+
+  /// Builds and initializes the actor system.
+  pub fn build(self) -> Result<ActorSystem<Msg>, ActorSystemBuildError> {
+    if !SYSTEM_NAME_REGEX.is_match(&self.name) {
+      return Err(ActorSystemBuildError::InvalidName(self.name));
+    }
+    if self.settings.throughput == 0 {
+      return Err(ActorSystemBuildError::InvalidThroughput);
+    }
+    if self.settings.scheduler_tick_duration < Duration::from_millis(1) {
+      return Err(ActorSystemBuildError::InvalidSchedulerTick(
+        self.settings.scheduler_tick_duration,
+      ));
+    }
+    let guardian_props = self.guardian_props.ok_or(ActorSystemBuildError::GuardianPropsNotSet)?;
+    let configured_dispatchers = self
+      .settings
+      .deployments
+      .iter()
+      .filter_map(|deploy| Some((deploy.path.clone(), deploy.dispatcher.clone()?)))
+      .chain(guardian_props.dispatcher().map(|dispatcher| ("/user/main".to_string(), dispatcher)));
+    for (path, dispatcher) in configured_dispatchers {
+      if !self.settings.dispatchers.iter().any(|settings| settings.name == dispatcher) {
+        return Err(ActorSystemBuildError::UnknownDispatcher { dispatcher, path });
+      }
+    }
+    let (runtime, runtime_handle) = match (self.runtime, self.runtime_handle) {
+      (_, Some(runtime_handle)) => (None, runtime_handle),
+      (Some(runtime), None) => {
+        let runtime_handle = runtime.handle().clone();
+        (Some(runtime), runtime_handle)
+      }
+      (None, None) => {
+        let runtime = runtime::Builder::new_multi_thread()
+          .enable_all()
+          .build()
+          .map_err(|error| ActorSystemBuildError::RuntimeCreationFailed(error.to_string()))?;
+        let runtime_handle = runtime.handle().clone();
+        (Some(runtime), runtime_handle)
+      }
+    };
+    let address = self
+      .address
+      .unwrap_or_else(|| Address::new(Self::DEFAULT_PROTOCOL, &self.name));
+    log::debug!("build: actor system {} at {}", self.name, address);
+    let mut actor_system = ActorSystem::new_with_settings(
+      runtime,
+      runtime_handle,
+      address,
+      &self.name,
+      guardian_props,
+      self.settings,
+    );
+    actor_system.initialize();
+    Ok(actor_system)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::core::actor::actor_cell::ActorCellBehavior;
+  use crate::core::actor::actor_context::{ActorContext, ActorContextBehavior};
+  use crate::core::actor::actor_ref::{ActorRefBehavior, AskError};
+  use crate::core::actor::deployer::Deploy;
+  use crate::core::actor::props::{FunctionProps, PropsSettingsBehavior};
+  use crate::core::actor::test_kit::{init_logger, EVENT_TIMEOUT};
+  use crate::core::actor::{ActorBehavior, ActorResult};
+  use std::cell::RefCell;
+
+  #[derive(Debug, Clone)]
+  struct EchoActor;
+
+  impl ActorBehavior<String> for EchoActor {
+    fn receive(&mut self, mut ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+      ctx.reply(format!("echo:{}", msg));
+      Ok(())
+    }
+  }
+
+  fn echo_props() -> Rc<dyn Props<String>> {
+    Rc::new(FunctionProps::new(|| Rc::new(RefCell::new(EchoActor))))
+  }
+
+  #[test]
+  fn test_build_starts_the_system() {
+    init_logger();
+    let actor_system = ActorSystemBuilder::new("orders")
+      .with_guardian_props(echo_props())
+      .with_throughput(5)
+      .with_scheduler_tick_duration(Duration::from_millis(5))
+      .with_default_mailbox_type(MailboxType::of_unbounded_deque())
+      .build()
+      .unwrap();
+
+    let mut guardian_ref = actor_system.guardian_ref();
+    assert_eq!(guardian_ref.path().to_string(), "actuator://orders/user/main");
+    assert_eq!(actor_system.settings().throughput, 5);
+    let guardian_cell = guardian_ref.actor_cell().unwrap();
+    assert_eq!(
+      guardian_cell.mailbox().mailbox_type(),
+      MailboxType::of_unbounded_deque()
+    );
+    let reply: Result<String, AskError> = guardian_ref.ask_blocking(|_| "a".to_string(), EVENT_TIMEOUT);
+    assert_eq!(reply, Ok("echo:a".to_string()));
+  }
+
+  #[test]
+  fn test_build_on_existing_runtime_handle() {
+    init_logger();
+    let runtime = runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+    let address = Address::new("tcp", "test");
+    let actor_system = ActorSystemBuilder::new("test")
+      .with_address(address)
+      .with_runtime_handle(runtime.handle().clone())
+      .with_guardian_props(echo_props())
+      .build()
+      .unwrap();
+
+    let mut service_ref = actor_system.actor_of(echo_props(), "service");
+    assert_eq!(service_ref.path().to_string(), "tcp://test/user/service");
+    let reply: Result<String, AskError> = service_ref.ask_blocking(|_| "b".to_string(), EVENT_TIMEOUT);
+    assert_eq!(reply, Ok("echo:b".to_string()));
+    assert_eq!(actor_system.terminate(), Ok(()));
+  }
+
+  #[test]
+  fn test_build_reports_invalid_settings() {
+    init_logger();
+    let result = ActorSystemBuilder::new("my system")
+      .with_guardian_props(echo_props())
+      .build();
+    assert_eq!(
+      result.err(),
+      Some(ActorSystemBuildError::InvalidName("my system".to_string()))
+    );
+    let result = ActorSystemBuilder::<String>::new("test").build();
+    assert_eq!(result.err(), Some(ActorSystemBuildError::GuardianPropsNotSet));
+    let result = ActorSystemBuilder::new("test")
+      .with_guardian_props(echo_props())
+      .with_throughput(0)
+      .build();
+    assert_eq!(result.err(), Some(ActorSystemBuildError::InvalidThroughput));
+    let result = ActorSystemBuilder::new("test")
+      .with_guardian_props(echo_props())
+      .with_scheduler_tick_duration(Duration::ZERO)
+      .build();
+    assert_eq!(
+      result.err(),
+      Some(ActorSystemBuildError::InvalidSchedulerTick(Duration::ZERO))
+    );
+    let settings = ActorSystemSettings {
+      deployments: vec![Deploy::new("/user/orders").with_dispatcher("missing")],
+      ..ActorSystemSettings::default()
+    };
+    let result = ActorSystemBuilder::new("test")
+      .with_guardian_props(echo_props())
+      .with_settings(settings)
+      .build();
+    assert_eq!(
+      result.err(),
+      Some(ActorSystemBuildError::UnknownDispatcher {
+        dispatcher: "missing".to_string(),
+        path: "/user/orders".to_string(),
+      })
+    );
+    let result = ActorSystemBuilder::new("test")
+      .with_guardian_props(Rc::new(
+        FunctionProps::new(|| Rc::new(RefCell::new(EchoActor))).with_dispatcher("missing"),
+      ))
+      .build();
+    assert_eq!(
+      result.err(),
+      Some(ActorSystemBuildError::UnknownDispatcher {
+        dispatcher: "missing".to_string(),
+        path: "/user/main".to_string(),
+      })
+    );
+  }
+}
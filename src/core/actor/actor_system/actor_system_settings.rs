@@ -0,0 +1,513 @@
+use crate::core::actor::deployer::Deploy;
+use crate::core::dispatch::dispatcher::Dispatcher;
+use crate::core::dispatch::mailbox::mailbox_type::MailboxType;
+use crate::core::routing::RouterConfig;
+use config::{Config, ConfigError, Environment, File, Value};
+use std::collections::HashMap;
+use std::time::Duration;
+use thiserror::Error;
+
+pub const DEFAULT_ENV_PREFIX: &str = "ACTUATOR";
+pub const DEFAULT_MAILBOX_PUSH_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ActorSystemSettingsError {
+  #[error("Failed to load the configuration: {0}")]
+  Load(String),
+  #[error("Invalid value for {key}: {message}")]
+  InvalidValue { key: String, message: String },
+}
+
+impl ActorSystemSettingsError {
+  fn invalid_value(key: &str, message: impl ToString) -> Self {
+    ActorSystemSettingsError::InvalidValue {
+      key: key.to_string(),
+      message: message.to_string(),
+    }
+  }
+}
+
+/// A dispatcher that deployments can refer to by `name`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DispatcherSettings {
+  pub name: String,
+  pub throughput: usize,
+  pub throughput_deadline: Option<Duration>,
+}
+
+/// The settings an actor system is started with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActorSystemSettings {
+  /// The mailbox type of actors whose props do not specify one.
+  pub default_mailbox_type: MailboxType,
+  /// The number of messages an actor processes before its mailbox yields to other actors.
+  pub throughput: usize,
+  /// The longest a mailbox keeps processing before it yields, if set.
+  pub throughput_deadline: Option<Duration>,
+  /// The resolution of the scheduler used for timers such as receive timeouts.
+  pub scheduler_tick_duration: Duration,
+  /// Whether undelivered messages are logged as warnings rather than at debug level.
+  pub log_dead_letters: bool,
+  /// The dispatchers besides the default one, sorted by name.
+  pub dispatchers: Vec<DispatcherSettings>,
+  /// The deployment overrides, sorted by path.
+  pub deployments: Vec<Deploy>,
+}
+
+impl Default for ActorSystemSettings {
+  fn default() -> Self {
+    Self {
+      default_mailbox_type: MailboxType::of_unbounded(),
+      throughput: Dispatcher::DEFAULT_THROUGHPUT,
+      throughput_deadline: None,
+      scheduler_tick_duration: Dispatcher::SCHEDULER_TICK_DURATION,
+      log_dead_letters: true,
+      dispatchers: Vec::new(),
+      deployments: Vec::new(),
+    }
+  }
+}
+
+impl ActorSystemSettings {
+  /// Loads the settings from `files`, each of which overrides the ones before it, and then from the environment
+  /// variables prefixed with `ACTUATOR_`. Settings found in none of them keep their defaults.
+  ///
+  /// The format of a file is told by its extension (`.toml`, `.yaml`, `.json`, ...), which may be omitted. In
+  /// environment variables `__` separates the keys, e.g. `ACTUATOR_DISPATCHER__THROUGHPUT=10`.
+  pub fn load(files: &[&str]) -> Result<Self, ActorSystemSettingsError> {
+    Self::load_with_env_prefix(files, DEFAULT_ENV_PREFIX)
+  }
+
+  pub fn load_with_env_prefix(files: &[&str], env_prefix: &str) -> Result<Self, ActorSystemSettingsError> {
+    let mut config = Config::new();
+    for file in files {
+      config
+        .merge(File::with_name(file))
+        .map_err(|error| ActorSystemSettingsError::Load(error.to_string()))?;
+    }
+    config
+      .merge(Environment::with_prefix(env_prefix).separator("__"))
+      .map_err(|error| ActorSystemSettingsError::Load(error.to_string()))?;
+    Self::from_config(&config)
+  }
+
+  /// Reads the settings from `config`:
+  ///
+  /// ```toml
+  /// [default_mailbox]
+  /// type = "bounded"          # "unbounded", "bounded" or "unbounded_deque"
+  /// capacity = 1000           # bounded only
+  /// push_timeout = "10s"      # bounded only
+  /// stash_capacity = 100      # unbounded_deque only
+  ///
+  /// [dispatcher]
+  /// throughput = 10
+  /// throughput_deadline = "50ms"
+  ///
+  /// [scheduler]
+  /// tick_duration = "10ms"
+  ///
+  /// [dead_letters]
+  /// log = false
+  ///
+  /// [dispatchers.ingest-dispatcher]
+  /// throughput = 100
+  ///
+  /// [deployment."/user/ingest/*"]
+  /// mailbox = { type = "bounded", capacity = 100 }
+  /// dispatcher = "ingest-dispatcher"
+  /// router = "round_robin_pool"   # "round_robin_pool", "random_pool" or "broadcast_pool"
+  /// nr_of_instances = 4
+  /// ```
+  pub fn from_config(config: &Config) -> Result<Self, ActorSystemSettingsError> {
+    let defaults = Self::default();
+    let default_mailbox_type = match optional("default_mailbox", config.get_table("default_mailbox"))? {
+      Some(table) => mailbox_type_from_table("default_mailbox", table)?,
+      None => defaults.default_mailbox_type,
+    };
+    let throughput = optional("dispatcher.throughput", config.get_int("dispatcher.throughput"))?
+      .map(|throughput| at_least_one("dispatcher.throughput", throughput))
+      .transpose()?
+      .unwrap_or(defaults.throughput);
+    let throughput_deadline = optional(
+      "dispatcher.throughput_deadline",
+      config.get_str("dispatcher.throughput_deadline"),
+    )?
+    .map(|value| parse_duration("dispatcher.throughput_deadline", &value))
+    .transpose()?;
+    let scheduler_tick_duration = optional("scheduler.tick_duration", config.get_str("scheduler.tick_duration"))?
+      .map(|value| parse_duration("scheduler.tick_duration", &value))
+      .transpose()?
+      .unwrap_or(defaults.scheduler_tick_duration);
+    let log_dead_letters =
+      optional("dead_letters.log", config.get_bool("dead_letters.log"))?.unwrap_or(defaults.log_dead_letters);
+    let mut dispatchers = match optional("dispatchers", config.get_table("dispatchers"))? {
+      Some(table) => table
+        .into_iter()
+        .map(|(name, value)| dispatcher_from_value(name, value, throughput))
+        .collect::<Result<Vec<_>, _>>()?,
+      None => defaults.dispatchers,
+    };
+    dispatchers.sort_by(|a, b| a.name.cmp(&b.name));
+    let mut deployments = match optional("deployment", config.get_table("deployment"))? {
+      Some(table) => table
+        .into_iter()
+        .map(|(path, value)| deployment_from_value(path, value))
+        .collect::<Result<Vec<_>, _>>()?,
+      None => defaults.deployments,
+    };
+    deployments.sort_by(|a, b| a.path.cmp(&b.path));
+    for deploy in &deployments {
+      if let Some(dispatcher) = &deploy.dispatcher {
+        if !dispatchers.iter().any(|settings| &settings.name == dispatcher) {
+          return Err(ActorSystemSettingsError::invalid_value(
+            &format!("deployment.{}.dispatcher", deploy.path),
+            format!("unknown dispatcher [{}]", dispatcher),
+          ));
+        }
+      }
+    }
+    Ok(Self {
+      default_mailbox_type,
+      throughput,
+      throughput_deadline,
+      scheduler_tick_duration,
+      log_dead_letters,
+      dispatchers,
+      deployments,
+    })
+  }
+}
+
+fn optional<T>(key: &str, result: Result<T, ConfigError>) -> Result<Option<T>, ActorSystemSettingsError> {
+  match result {
+    Ok(value) => Ok(Some(value)),
+    Err(ConfigError::NotFound(_)) => Ok(None),
+    Err(error) => Err(ActorSystemSettingsError::invalid_value(key, error)),
+  }
+}
+
+fn at_least_one(key: &str, value: i64) -> Result<usize, ActorSystemSettingsError> {
+  if value < 1 {
+    Err(ActorSystemSettingsError::invalid_value(key, "must be at least 1"))
+  } else {
+    Ok(value as usize)
+  }
+}
+
+fn dispatcher_from_value(
+  name: String,
+  value: Value,
+  default_throughput: usize,
+) -> Result<DispatcherSettings, ActorSystemSettingsError> {
+  let key = format!("dispatchers.{}", name);
+  let mut table = value
+    .into_table()
+    .map_err(|error| ActorSystemSettingsError::invalid_value(&key, error))?;
+  let throughput = match table.remove("throughput") {
+    Some(value) => value
+      .into_int()
+      .map_err(|error| ActorSystemSettingsError::invalid_value(&format!("{}.throughput", key), error))
+      .and_then(|throughput| at_least_one(&format!("{}.throughput", key), throughput))?,
+    None => default_throughput,
+  };
+  let throughput_deadline = table
+    .remove("throughput_deadline")
+    .map(|value| {
+      let deadline_key = format!("{}.throughput_deadline", key);
+      value
+        .into_str()
+        .map_err(|error| ActorSystemSettingsError::invalid_value(&deadline_key, error))
+        .and_then(|value| parse_duration(&deadline_key, &value))
+    })
+    .transpose()?;
+  Ok(DispatcherSettings {
+    name,
+    throughput,
+    throughput_deadline,
+  })
+}
+
+fn deployment_from_value(path: String, value: Value) -> Result<Deploy, ActorSystemSettingsError> {
+  let key = format!("deployment.{}", path);
+  let mut table = value
+    .into_table()
+    .map_err(|error| ActorSystemSettingsError::invalid_value(&key, error))?;
+  let mailbox_type = match table.remove("mailbox") {
+    Some(value) => {
+      let mailbox_key = format!("{}.mailbox", key);
+      let mailbox_table = value
+        .into_table()
+        .map_err(|error| ActorSystemSettingsError::invalid_value(&mailbox_key, error))?;
+      Some(mailbox_type_from_table(&mailbox_key, mailbox_table)?)
+    }
+    None => None,
+  };
+  let dispatcher = table
+    .remove("dispatcher")
+    .map(|value| value.into_str())
+    .transpose()
+    .map_err(|error| ActorSystemSettingsError::invalid_value(&key, error))?;
+  let router_config = router_config_from_table(&key, &mut table)?;
+  Ok(Deploy {
+    path,
+    mailbox_type,
+    dispatcher,
+    router_config,
+  })
+}
+
+fn router_config_from_table(
+  key: &str,
+  table: &mut HashMap<String, Value>,
+) -> Result<Option<RouterConfig>, ActorSystemSettingsError> {
+  let router_key = format!("{}.router", key);
+  let router = match table.remove("router") {
+    Some(value) => value
+      .into_str()
+      .map_err(|error| ActorSystemSettingsError::invalid_value(&router_key, error))?,
+    None => return Ok(None),
+  };
+  let instances_key = format!("{}.nr_of_instances", key);
+  let nr_of_instances = match table.remove("nr_of_instances") {
+    Some(value) => value
+      .into_int()
+      .map_err(|error| ActorSystemSettingsError::invalid_value(&instances_key, error))
+      .and_then(|nr_of_instances| at_least_one(&instances_key, nr_of_instances))?,
+    None => {
+      return Err(ActorSystemSettingsError::invalid_value(
+        &instances_key,
+        "a pool router needs it",
+      ))
+    }
+  };
+  match router.as_str() {
+    "round_robin_pool" => Ok(Some(RouterConfig::of_round_robin_pool(nr_of_instances))),
+    "random_pool" => Ok(Some(RouterConfig::of_random_pool(nr_of_instances))),
+    "broadcast_pool" => Ok(Some(RouterConfig::of_broadcast_pool(nr_of_instances))),
+    other => Err(ActorSystemSettingsError::invalid_value(
+      &router_key,
+      format!("unknown router [{}]", other),
+    )),
+  }
+}
+
+fn mailbox_type_from_table(
+  key: &str,
+  mut table: HashMap<String, Value>,
+) -> Result<MailboxType, ActorSystemSettingsError> {
+  let mut take_int = |name: &str| -> Result<Option<usize>, ActorSystemSettingsError> {
+    let value_key = format!("{}.{}", key, name);
+    match table.remove(name).map(Value::into_int).transpose() {
+      Ok(Some(value)) if value < 0 => Err(ActorSystemSettingsError::invalid_value(
+        &value_key,
+        "must not be negative",
+      )),
+      Ok(value) => Ok(value.map(|value| value as usize)),
+      Err(error) => Err(ActorSystemSettingsError::invalid_value(&value_key, error)),
+    }
+  };
+  let capacity = take_int("capacity")?;
+  let stash_capacity = take_int("stash_capacity")?;
+  let push_timeout = table
+    .remove("push_timeout")
+    .map(|value| {
+      let push_timeout_key = format!("{}.push_timeout", key);
+      value
+        .into_str()
+        .map_err(|error| ActorSystemSettingsError::invalid_value(&push_timeout_key, error))
+        .and_then(|value| parse_duration(&push_timeout_key, &value))
+    })
+    .transpose()?;
+  let mailbox_type = table
+    .remove("type")
+    .map(Value::into_str)
+    .transpose()
+    .map_err(|error| ActorSystemSettingsError::invalid_value(&format!("{}.type", key), error))?;
+  match mailbox_type.as_deref().unwrap_or("unbounded") {
+    "unbounded" => Ok(MailboxType::of_unbounded()),
+    "bounded" => match capacity {
+      Some(capacity) if capacity > 0 => Ok(MailboxType::of_bounded(
+        capacity,
+        push_timeout.unwrap_or(DEFAULT_MAILBOX_PUSH_TIMEOUT),
+      )),
+      _ => Err(ActorSystemSettingsError::invalid_value(
+        &format!("{}.capacity", key),
+        "a bounded mailbox needs a capacity of at least 1",
+      )),
+    },
+    "unbounded_deque" => Ok(match stash_capacity {
+      Some(stash_capacity) => MailboxType::of_unbounded_deque_with_stash_capacity(stash_capacity),
+      None => MailboxType::of_unbounded_deque(),
+    }),
+    other => Err(ActorSystemSettingsError::invalid_value(
+      &format!("{}.type", key),
+      format!("unknown mailbox type [{}]", other),
+    )),
+  }
+}
+
+/// Parses durations such as `500us`, `10ms`, `3s` or `1m`; a bare number is taken as milliseconds.
+fn parse_duration(key: &str, value: &str) -> Result<Duration, ActorSystemSettingsError> {
+  let value = value.trim();
+  let split_at = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+  let (amount, unit) = value.split_at(split_at);
+  let invalid_duration = || ActorSystemSettingsError::invalid_value(key, format!("invalid duration [{}]", value));
+  let amount = amount.parse::<u64>().map_err(|_| invalid_duration())?;
+  match unit.trim() {
+    "ns" => Ok(Duration::from_nanos(amount)),
+    "us" => Ok(Duration::from_micros(amount)),
+    "" | "ms" => Ok(Duration::from_millis(amount)),
+    "s" => Ok(Duration::from_secs(amount)),
+    "m" => amount
+      .checked_mul(60)
+      .map(Duration::from_secs)
+      .ok_or_else(invalid_duration),
+    _ => Err(invalid_duration()),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use config::FileFormat;
+  use std::env;
+
+  const TOML: &str = r#"
+    [default_mailbox]
+    type = "bounded"
+    capacity = 1000
+    push_timeout = "100ms"
+
+    [dispatcher]
+    throughput = 10
+    throughput_deadline = "50ms"
+
+    [scheduler]
+    tick_duration = "5ms"
+
+    [dead_letters]
+    log = false
+
+    [dispatchers.ingest-dispatcher]
+    throughput = 100
+    throughput_deadline = "20ms"
+
+    [dispatchers.audit-dispatcher]
+
+    [deployment."/user/ingest/*"]
+    mailbox = { type = "unbounded_deque", stash_capacity = 16 }
+    dispatcher = "ingest-dispatcher"
+    router = "round_robin_pool"
+    nr_of_instances = 4
+
+    [deployment."/user/audit"]
+    dispatcher = "audit-dispatcher"
+  "#;
+
+  fn config_of(sources: &[(&str, FileFormat)]) -> Config {
+    let mut config = Config::new();
+    for (source, format) in sources {
+      config.merge(File::from_str(source, *format)).unwrap();
+    }
+    config
+  }
+
+  #[test]
+  fn test_from_config_reads_every_setting() {
+    let settings = ActorSystemSettings::from_config(&config_of(&[(TOML, FileFormat::Toml)])).unwrap();
+    assert_eq!(
+      settings,
+      ActorSystemSettings {
+        default_mailbox_type: MailboxType::of_bounded(1000, Duration::from_millis(100)),
+        throughput: 10,
+        throughput_deadline: Some(Duration::from_millis(50)),
+        scheduler_tick_duration: Duration::from_millis(5),
+        log_dead_letters: false,
+        dispatchers: vec![
+          DispatcherSettings {
+            name: "audit-dispatcher".to_string(),
+            throughput: 10,
+            throughput_deadline: None,
+          },
+          DispatcherSettings {
+            name: "ingest-dispatcher".to_string(),
+            throughput: 100,
+            throughput_deadline: Some(Duration::from_millis(20)),
+          },
+        ],
+        deployments: vec![
+          Deploy::new("/user/audit").with_dispatcher("audit-dispatcher"),
+          Deploy::new("/user/ingest/*")
+            .with_mailbox_type(MailboxType::of_unbounded_deque_with_stash_capacity(16))
+            .with_dispatcher("ingest-dispatcher")
+            .with_router_config(RouterConfig::of_round_robin_pool(4)),
+        ],
+      }
+    );
+  }
+
+  #[test]
+  fn test_from_config_layers_sources_over_defaults() {
+    let yaml = "dispatcher:\n  throughput: 3\n";
+    let json = r#"{ "default_mailbox": { "type": "unbounded_deque" } }"#;
+    let config = config_of(&[
+      (TOML, FileFormat::Toml),
+      (yaml, FileFormat::Yaml),
+      (json, FileFormat::Json),
+    ]);
+    let settings = ActorSystemSettings::from_config(&config).unwrap();
+    assert_eq!(settings.throughput, 3);
+    assert_eq!(settings.default_mailbox_type, MailboxType::of_unbounded_deque());
+    assert_eq!(settings.scheduler_tick_duration, Duration::from_millis(5));
+
+    let settings = ActorSystemSettings::from_config(&Config::new()).unwrap();
+    assert_eq!(settings, ActorSystemSettings::default());
+  }
+
+  #[test]
+  fn test_load_applies_environment_overrides() {
+    let dir = env::temp_dir().join(format!("actuator-settings-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("actuator.toml");
+    std::fs::write(&file, TOML).unwrap();
+    env::set_var("ACTUATOR_SETTINGS_TEST_DISPATCHER__THROUGHPUT", "42");
+    env::set_var("ACTUATOR_SETTINGS_TEST_DEFAULT_MAILBOX__CAPACITY", "64");
+
+    let settings =
+      ActorSystemSettings::load_with_env_prefix(&[file.to_str().unwrap()], "ACTUATOR_SETTINGS_TEST").unwrap();
+    assert_eq!(settings.throughput, 42);
+    assert_eq!(
+      settings.default_mailbox_type,
+      MailboxType::of_bounded(64, Duration::from_millis(100))
+    );
+    assert_eq!(settings.deployments.len(), 2);
+
+    let result = ActorSystemSettings::load_with_env_prefix(&["no-such-file"], "ACTUATOR_SETTINGS_TEST");
+    assert!(matches!(result, Err(ActorSystemSettingsError::Load(_))));
+    std::fs::remove_dir_all(&dir).unwrap();
+  }
+
+  #[test]
+  fn test_from_config_reports_invalid_values() {
+    let invalid = [
+      "[dispatcher]\nthroughput = 0",
+      "[scheduler]\ntick_duration = \"10 fortnights\"",
+      "[scheduler]\ntick_duration = \"18446744073709551615m\"",
+      "[default_mailbox]\ntype = \"bounded\"",
+      "[default_mailbox]\ntype = \"priority\"",
+      "[deployment.\"/user/a\"]\ndispatcher = \"missing\"",
+      "[deployment.\"/user/a\"]\nrouter = \"round_robin_pool\"",
+      "[deployment.\"/user/a\"]\nrouter = \"smallest_mailbox_pool\"\nnr_of_instances = 2",
+    ];
+    for toml in invalid {
+      let result = ActorSystemSettings::from_config(&config_of(&[(toml, FileFormat::Toml)]));
+      assert!(
+        matches!(result, Err(ActorSystemSettingsError::InvalidValue { .. })),
+        "{}: {:?}",
+        toml,
+        result
+      );
+    }
+  }
+}
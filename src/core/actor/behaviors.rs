@@ -0,0 +1,465 @@
+use crate::core::actor::actor_context::{ActorContext, ActorContextBehavior};
+use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior};
+use crate::core::actor::behaviors::timer_scheduler::TimerScheduler;
+use crate::core::actor::props::FunctionProps;
+use crate::core::actor::{ActorBehavior, ActorError, ActorResult};
+use crate::core::dispatch::any_message::AnyMessage;
+use crate::core::dispatch::message::Message;
+use std::cell::RefCell;
+use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
+
+pub mod timer_scheduler;
+
+/// A lifecycle event delivered to the signal handler of a behavior.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Signal {
+  /// The actor is about to be restarted; the behavior returned by the handler is ignored.
+  PreRestart,
+  /// The actor has stopped; the behavior returned by the handler is ignored.
+  PostStop,
+  /// A watched actor has terminated.
+  Terminated(Box<ActorRef<AnyMessage>>),
+}
+
+pub type MessageHandler<Msg> = Rc<dyn Fn(ActorContext<Msg>, Msg) -> ActorResult<Behavior<Msg>>>;
+pub type SignalHandler<Msg> = Rc<dyn Fn(ActorContext<Msg>, Signal) -> ActorResult<Behavior<Msg>>>;
+
+/// How an actor handles the next message, created through `Behaviors`.
+///
+/// A handler returns the behavior for the messages after the current one, so state is carried by returning a new
+/// behavior that captures it.
+#[derive(Clone)]
+pub enum Behavior<Msg: Message> {
+  Setup(Rc<dyn Fn(ActorContext<Msg>) -> ActorResult<Behavior<Msg>>>),
+  WithTimers(Rc<dyn Fn(TimerScheduler<Msg>) -> ActorResult<Behavior<Msg>>>),
+  Receive {
+    on_message: Option<MessageHandler<Msg>>,
+    on_signal: Option<SignalHandler<Msg>>,
+  },
+  Same,
+  Stopped,
+  Unhandled,
+}
+
+impl<Msg: Message> Debug for Behavior<Msg> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Behavior::Setup(_) => write!(f, "Setup"),
+      Behavior::WithTimers(_) => write!(f, "WithTimers"),
+      Behavior::Receive { on_message, on_signal } => f
+        .debug_struct("Receive")
+        .field("on_message", &on_message.is_some())
+        .field("on_signal", &on_signal.is_some())
+        .finish(),
+      Behavior::Same => write!(f, "Same"),
+      Behavior::Stopped => write!(f, "Stopped"),
+      Behavior::Unhandled => write!(f, "Unhandled"),
+    }
+  }
+}
+
+impl<Msg: Message> Behavior<Msg> {
+  /// Adds `f` as the signal handler of this behavior; for `Setup` and `WithTimers` it is added to the behavior they
+  /// create.
+  ///
+  /// `Same`, `Stopped` and `Unhandled` have no handlers, so they are returned unchanged.
+  pub fn receive_signal<F>(self, f: F) -> Self
+  where
+    F: Fn(ActorContext<Msg>, Signal) -> ActorResult<Behavior<Msg>> + 'static, {
+    self.with_signal_handler(Rc::new(f))
+  }
+
+  fn with_signal_handler(self, on_signal: SignalHandler<Msg>) -> Self {
+    match self {
+      Behavior::Setup(f) => Behavior::Setup(Rc::new(move |ctx| {
+        f(ctx).map(|behavior| behavior.with_signal_handler(on_signal.clone()))
+      })),
+      Behavior::WithTimers(f) => Behavior::WithTimers(Rc::new(move |timers| {
+        f(timers).map(|behavior| behavior.with_signal_handler(on_signal.clone()))
+      })),
+      Behavior::Receive { on_message, .. } => Behavior::Receive {
+        on_message,
+        on_signal: Some(on_signal),
+      },
+      other => {
+        log::warn!("receive_signal: {:?} does not take a signal handler, it is ignored", other);
+        other
+      }
+    }
+  }
+
+  /// Returns props that create an actor starting with this behavior, also when it is restarted.
+  pub fn to_props(self) -> FunctionProps<Msg> {
+    FunctionProps::new(move || Rc::new(RefCell::new(BehaviorActor::new(self.clone()))))
+  }
+}
+
+/// Creates behaviors in the style of Akka Typed.
+pub struct Behaviors;
+
+impl Behaviors {
+  /// Defers creating the behavior until the actor starts, so that `f` can use its context.
+  pub fn setup<Msg: Message, F>(f: F) -> Behavior<Msg>
+  where
+    F: Fn(ActorContext<Msg>) -> ActorResult<Behavior<Msg>> + 'static, {
+    Behavior::Setup(Rc::new(f))
+  }
+
+  pub fn receive_message<Msg: Message, F>(f: F) -> Behavior<Msg>
+  where
+    F: Fn(ActorContext<Msg>, Msg) -> ActorResult<Behavior<Msg>> + 'static, {
+    Behavior::Receive {
+      on_message: Some(Rc::new(f)),
+      on_signal: None,
+    }
+  }
+
+  /// A behavior that only handles signals and leaves every message unhandled.
+  pub fn receive_signal<Msg: Message, F>(f: F) -> Behavior<Msg>
+  where
+    F: Fn(ActorContext<Msg>, Signal) -> ActorResult<Behavior<Msg>> + 'static, {
+    Behavior::Receive {
+      on_message: None,
+      on_signal: Some(Rc::new(f)),
+    }
+  }
+
+  /// Passes the timers of the actor to `f` when the actor starts.
+  pub fn with_timers<Msg: Message, F>(f: F) -> Behavior<Msg>
+  where
+    F: Fn(TimerScheduler<Msg>) -> ActorResult<Behavior<Msg>> + 'static, {
+    Behavior::WithTimers(Rc::new(f))
+  }
+
+  /// Keeps the current behavior.
+  pub fn same<Msg: Message>() -> Behavior<Msg> {
+    Behavior::Same
+  }
+
+  /// Stops the actor; the signal handler of the current behavior receives `Signal::PostStop`.
+  pub fn stopped<Msg: Message>() -> Behavior<Msg> {
+    Behavior::Stopped
+  }
+
+  /// Keeps the current behavior and reports the message as unhandled.
+  pub fn unhandled<Msg: Message>() -> Behavior<Msg> {
+    Behavior::Unhandled
+  }
+
+  /// A behavior that leaves every message and signal unhandled.
+  pub fn empty<Msg: Message>() -> Behavior<Msg> {
+    Behavior::Receive {
+      on_message: None,
+      on_signal: None,
+    }
+  }
+}
+
+/// Runs a `Behavior` as an `ActorBehavior`.
+pub struct BehaviorActor<Msg: Message> {
+  initial: Behavior<Msg>,
+  current: Behavior<Msg>,
+  timers: Option<TimerScheduler<Msg>>,
+  stopping: bool,
+}
+
+impl<Msg: Message> Debug for BehaviorActor<Msg> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("BehaviorActor")
+      .field("current", &self.current)
+      .field("stopping", &self.stopping)
+      .finish()
+  }
+}
+
+impl<Msg: Message> BehaviorActor<Msg> {
+  pub fn new(initial: Behavior<Msg>) -> Self {
+    Self {
+      initial,
+      current: Behaviors::empty(),
+      timers: None,
+      stopping: false,
+    }
+  }
+
+  /// Makes `next` the current behavior, running the setups and timer factories it starts with.
+  fn transition(&mut self, ctx: &mut ActorContext<Msg>, next: Behavior<Msg>) -> ActorResult<()> {
+    match next {
+      Behavior::Setup(f) => {
+        let next = f(ctx.clone())?;
+        self.transition(ctx, next)
+      }
+      Behavior::WithTimers(f) => {
+        let timers = self.timers.get_or_insert_with(|| TimerScheduler::new(ctx)).clone();
+        let next = f(timers)?;
+        self.transition(ctx, next)
+      }
+      Behavior::Same => Ok(()),
+      Behavior::Unhandled => {
+        log::debug!("transition: unhandled by {}", ctx.self_ref().path());
+        Ok(())
+      }
+      Behavior::Stopped => {
+        self.stopping = true;
+        ctx.stop(ctx.self_ref());
+        Ok(())
+      }
+      receive @ Behavior::Receive { .. } => {
+        self.current = receive;
+        Ok(())
+      }
+    }
+  }
+
+  fn signal(&self, ctx: &ActorContext<Msg>, signal: Signal) -> ActorResult<Behavior<Msg>> {
+    match &self.current {
+      Behavior::Receive {
+        on_signal: Some(on_signal),
+        ..
+      } => on_signal.clone()(ctx.clone(), signal),
+      _ => Ok(Behavior::Unhandled),
+    }
+  }
+
+  fn cancel_timers(&mut self) {
+    if let Some(timers) = self.timers.take() {
+      timers.cancel_all();
+    }
+  }
+}
+
+impl<Msg: Message> ActorBehavior<Msg> for BehaviorActor<Msg> {
+  fn receive(&mut self, mut ctx: ActorContext<Msg>, msg: Msg) -> ActorResult<()> {
+    if self.stopping {
+      log::debug!("receive: dropping {:?} while stopping", msg);
+      return Ok(());
+    }
+    let on_message = match &self.current {
+      Behavior::Receive {
+        on_message: Some(on_message),
+        ..
+      } => on_message.clone(),
+      _ => return self.transition(&mut ctx, Behavior::Unhandled),
+    };
+    let next = on_message(ctx.clone(), msg)?;
+    self.transition(&mut ctx, next)
+  }
+
+  fn pre_start(&mut self, mut ctx: ActorContext<Msg>) -> ActorResult<()> {
+    self.transition(&mut ctx, self.initial.clone())
+  }
+
+  fn pre_restart(&mut self, ctx: ActorContext<Msg>, _reason: ActorError, _msg: Option<Msg>) -> ActorResult<()> {
+    self.cancel_timers();
+    self.signal(&ctx, Signal::PreRestart).map(|_| ())
+  }
+
+  fn post_stop(&mut self, ctx: ActorContext<Msg>) -> ActorResult<()> {
+    self.cancel_timers();
+    let result = self.signal(&ctx, Signal::PostStop).map(|_| ());
+    // Drops the handlers, which may hold references to this actor.
+    self.current = Behaviors::empty();
+    result
+  }
+
+  fn terminated(&mut self, mut ctx: ActorContext<Msg>, actor: ActorRef<AnyMessage>) -> ActorResult<()> {
+    let next = self.signal(&ctx, Signal::Terminated(Box::new(actor)))?;
+    self.transition(&mut ctx, next)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::core::actor::actor_path::ActorPathBehavior;
+  use crate::core::actor::actor_system::ActorSystem;
+  use crate::core::actor::test_kit::{start_system, EventProbe};
+  use std::sync::mpsc::Sender;
+  use std::time::Duration;
+
+  #[derive(Debug, Clone, PartialEq)]
+  enum Command {
+    Increment,
+    Report,
+    Tick,
+    Fail,
+    Stop,
+  }
+
+  fn new_system() -> ActorSystem<Command> {
+    let (actor_system, _) = start_system(Rc::new(Behaviors::empty().to_props()));
+    actor_system
+  }
+
+  fn counter(count: u32, events_tx: Sender<String>) -> Behavior<Command> {
+    let signal_tx = events_tx.clone();
+    Behaviors::receive_message(move |_ctx, msg| match msg {
+      Command::Increment => Ok(counter(count + 1, events_tx.clone())),
+      Command::Report => {
+        events_tx.send(format!("count:{}", count)).unwrap();
+        Ok(Behaviors::same())
+      }
+      Command::Fail => Err(ActorError::ActorFailed {
+        message: "fail".to_string(),
+      }),
+      Command::Stop => Ok(Behaviors::stopped()),
+      Command::Tick => Ok(Behaviors::unhandled()),
+    })
+    .receive_signal(move |_ctx, signal| {
+      match signal {
+        Signal::PreRestart => signal_tx.send(format!("pre_restart:{}", count)).unwrap(),
+        Signal::PostStop => signal_tx.send(format!("post_stop:{}", count)).unwrap(),
+        Signal::Terminated(_) => {}
+      }
+      Ok(Behaviors::same())
+    })
+  }
+
+  #[test]
+  fn test_behaviors_carry_state_and_receive_signals() {
+    let probe = EventProbe::new();
+    let events_tx = probe.events_tx();
+    let actor_system = new_system();
+    let behavior = Behaviors::setup(move |ctx| {
+      events_tx
+        .send(format!("setup:{}", ctx.self_ref().path().name()))
+        .unwrap();
+      Ok(counter(0, events_tx.clone()))
+    });
+    let mut counter_ref = actor_system.actor_of(Rc::new(behavior.to_props()), "counter");
+
+    counter_ref.tell(Command::Increment);
+    counter_ref.tell(Command::Increment);
+    counter_ref.tell(Command::Tick);
+    counter_ref.tell(Command::Report);
+    assert_eq!(probe.next(), "setup:counter");
+    assert_eq!(probe.next(), "count:2");
+
+    // A restart starts over from the initial behavior.
+    counter_ref.tell(Command::Fail);
+    counter_ref.tell(Command::Increment);
+    counter_ref.tell(Command::Report);
+    assert_eq!(probe.next(), "pre_restart:2");
+    assert_eq!(probe.next(), "setup:counter");
+    assert_eq!(probe.next(), "count:1");
+
+    counter_ref.tell(Command::Stop);
+    counter_ref.tell(Command::Report);
+    assert_eq!(probe.next(), "post_stop:1");
+    probe.expect_no_event(Duration::from_millis(100));
+  }
+
+  fn ticker(ticks: u32, timers: TimerScheduler<Command>, events_tx: Sender<String>) -> Behavior<Command> {
+    Behaviors::receive_message(move |_ctx, msg| match msg {
+      Command::Tick => {
+        events_tx.send(format!("tick:{}", ticks + 1)).unwrap();
+        if ticks + 1 == 3 {
+          timers.cancel("tick");
+        }
+        Ok(ticker(ticks + 1, timers.clone(), events_tx.clone()))
+      }
+      Command::Report => {
+        events_tx.send("report".to_string()).unwrap();
+        Ok(Behaviors::same())
+      }
+      _ => Ok(Behaviors::unhandled()),
+    })
+  }
+
+  #[test]
+  fn test_with_timers_schedules_messages_to_self() {
+    let probe = EventProbe::new();
+    let events_tx = probe.events_tx();
+    let actor_system = new_system();
+    let behavior = Behaviors::with_timers(move |timers| {
+      timers.start_timer_with_fixed_delay("tick", Command::Tick, Duration::from_millis(20));
+      timers.start_single_timer("report", Command::Report, Duration::from_millis(500));
+      Ok(ticker(0, timers, events_tx.clone()))
+    });
+    actor_system.actor_of(Rc::new(behavior.to_props()), "ticker");
+
+    assert_eq!(probe.next(), "tick:1");
+    assert_eq!(probe.next(), "tick:2");
+    assert_eq!(probe.next(), "tick:3");
+    assert_eq!(probe.next(), "report");
+    probe.expect_no_event(Duration::from_millis(100));
+  }
+
+  /// Keeps the actor busy while letting the timers of the runtime run.
+  fn busy(duration: Duration) {
+    tokio::task::block_in_place(|| std::thread::sleep(duration));
+  }
+
+  #[test]
+  fn test_timers_drop_messages_sent_before_cancel_or_replace() {
+    let probe = EventProbe::new();
+    let events_tx = probe.events_tx();
+    let actor_system = new_system();
+    let behavior = Behaviors::with_timers(move |timers| {
+      timers.start_timer_with_fixed_delay("tick", Command::Tick, Duration::from_millis(20));
+      timers.start_single_timer("report", Command::Report, Duration::from_millis(20));
+      // Both timers send while the actor is busy, so their first messages are already queued when they change.
+      busy(Duration::from_millis(100));
+      timers.start_single_timer("report", Command::Increment, Duration::from_millis(20));
+      let events_tx = events_tx.clone();
+      Ok(Behaviors::receive_message(move |_ctx, msg| {
+        match msg {
+          Command::Tick => {
+            events_tx.send("tick".to_string()).unwrap();
+            busy(Duration::from_millis(100));
+            timers.cancel("tick");
+          }
+          Command::Report => events_tx.send("report".to_string()).unwrap(),
+          Command::Increment => events_tx.send("increment".to_string()).unwrap(),
+          _ => {}
+        }
+        Ok(Behaviors::same())
+      }))
+    });
+    actor_system.actor_of(Rc::new(behavior.to_props()), "timers");
+
+    assert_eq!(probe.next(), "tick");
+    assert_eq!(probe.next(), "increment");
+    probe.expect_no_event(Duration::from_millis(200));
+  }
+
+  #[test]
+  fn test_receive_signal_applies_to_the_behavior_created_by_setup() {
+    let probe = EventProbe::new();
+    let events_tx = probe.events_tx();
+    let actor_system = new_system();
+    let setup_events_tx = events_tx.clone();
+    let behavior = Behaviors::setup(move |_ctx| {
+      let events_tx = setup_events_tx.clone();
+      Ok(Behaviors::with_timers(move |_timers| {
+        let events_tx = events_tx.clone();
+        Ok(Behaviors::receive_message(move |_ctx, msg| match msg {
+          Command::Stop => Ok(Behaviors::stopped()),
+          _ => {
+            events_tx.send(format!("{:?}", msg)).unwrap();
+            Ok(Behaviors::same())
+          }
+        }))
+      }))
+    })
+    .receive_signal(move |_ctx, signal| {
+      events_tx.send(format!("signal:{:?}", signal)).unwrap();
+      Ok(Behaviors::same())
+    });
+    let mut actor_ref = actor_system.actor_of(Rc::new(behavior.to_props()), "setup");
+
+    actor_ref.tell(Command::Report);
+    actor_ref.tell(Command::Stop);
+    assert_eq!(probe.next(), "Report");
+    assert_eq!(probe.next(), "signal:PostStop");
+  }
+
+  #[test]
+  fn test_receive_signal_keeps_behaviors_without_handlers() {
+    let on_signal = |_ctx, _signal| Ok(Behaviors::same());
+    assert!(matches!(Behaviors::same::<Command>().receive_signal(on_signal), Behavior::Same));
+    assert!(matches!(Behaviors::stopped::<Command>().receive_signal(on_signal), Behavior::Stopped));
+    assert!(matches!(Behaviors::unhandled::<Command>().receive_signal(on_signal), Behavior::Unhandled));
+  }
+}
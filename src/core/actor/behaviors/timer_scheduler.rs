@@ -0,0 +1,135 @@
+use crate::core::actor::actor_context::ActorContext;
+use crate::core::actor::actor_ref::ActorRef;
+use crate::core::actor::scheduler::Cancellable;
+use crate::core::dispatch::dispatcher::Dispatcher;
+use crate::core::dispatch::message::Message;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+static TIMER_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// A message sent by a timer, tagged with the generation of the timer that sent it.
+#[derive(Debug, Clone, PartialEq)]
+struct TimerMsg<Msg: Message> {
+  key: String,
+  generation: u64,
+  repeat: bool,
+  message: Msg,
+}
+
+#[derive(Debug)]
+struct Timer {
+  generation: u64,
+  cancellable: Cancellable,
+}
+
+/// Schedules messages to the actor itself under a key; starting a timer cancels the one with the same key.
+///
+/// The timers are cancelled when the actor stops or restarts. A message that was already sent by a timer which
+/// has since been cancelled or replaced is dropped instead of being delivered.
+#[derive(Clone)]
+pub struct TimerScheduler<Msg: Message> {
+  timer_ref: ActorRef<TimerMsg<Msg>>,
+  dispatcher: Dispatcher,
+  timers: Rc<RefCell<HashMap<String, Timer>>>,
+}
+
+impl<Msg: Message> Debug for TimerScheduler<Msg> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("TimerScheduler")
+      .field("timer_ref", &self.timer_ref)
+      .field("keys", &self.timers.borrow().keys().collect::<Vec<_>>())
+      .finish()
+  }
+}
+
+impl<Msg: Message> TimerScheduler<Msg> {
+  pub(crate) fn new(ctx: &ActorContext<Msg>) -> Self {
+    let timers: Rc<RefCell<HashMap<String, Timer>>> = Rc::new(RefCell::new(HashMap::new()));
+    let cloned_timers = timers.clone();
+    let timer_ref = ctx.filtering_message_adaptor(move |timer_msg| Self::accept(&cloned_timers, timer_msg));
+    Self {
+      timer_ref,
+      dispatcher: ctx.dispatcher(),
+      timers,
+    }
+  }
+
+  /// Unwraps `timer_msg` if it was sent by the current timer of its key.
+  fn accept(timers: &RefCell<HashMap<String, Timer>>, timer_msg: TimerMsg<Msg>) -> Option<Msg> {
+    let mut timers = timers.borrow_mut();
+    match timers.get(&timer_msg.key) {
+      Some(timer) if timer.generation == timer_msg.generation => {
+        if !timer_msg.repeat {
+          timers.remove(&timer_msg.key);
+        }
+        Some(timer_msg.message)
+      }
+      _ => {
+        log::debug!("accept: dropping a stale message of timer {}", timer_msg.key);
+        None
+      }
+    }
+  }
+
+  /// Sends `msg` once after `delay`.
+  pub fn start_single_timer(&self, key: &str, msg: Msg, delay: Duration) {
+    let generation = TIMER_GENERATION.fetch_add(1, Ordering::Relaxed);
+    let cancellable = self.dispatcher.scheduler().schedule_once_to_actor_ref(
+      self.dispatcher.runtime(),
+      delay,
+      self.timer_ref.clone(),
+      Self::timer_msg(key, generation, false, msg),
+    );
+    self.replace(key, generation, cancellable);
+  }
+
+  /// Sends `msg` every `delay`, starting after `delay`.
+  pub fn start_timer_with_fixed_delay(&self, key: &str, msg: Msg, delay: Duration) {
+    let generation = TIMER_GENERATION.fetch_add(1, Ordering::Relaxed);
+    let cancellable = self.dispatcher.scheduler().schedule_with_fixed_delay_to_actor_ref(
+      self.dispatcher.runtime(),
+      delay,
+      delay,
+      self.timer_ref.clone(),
+      Self::timer_msg(key, generation, true, msg),
+    );
+    self.replace(key, generation, cancellable);
+  }
+
+  fn timer_msg(key: &str, generation: u64, repeat: bool, message: Msg) -> TimerMsg<Msg> {
+    TimerMsg {
+      key: key.to_string(),
+      generation,
+      repeat,
+      message,
+    }
+  }
+
+  fn replace(&self, key: &str, generation: u64, cancellable: Cancellable) {
+    let timer = Timer {
+      generation,
+      cancellable,
+    };
+    if let Some(previous) = self.timers.borrow_mut().insert(key.to_string(), timer) {
+      log::debug!("replace: cancelling the previous timer {}", key);
+      previous.cancellable.cancel();
+    }
+  }
+
+  pub fn cancel(&self, key: &str) {
+    if let Some(timer) = self.timers.borrow_mut().remove(key) {
+      timer.cancellable.cancel();
+    }
+  }
+
+  pub fn cancel_all(&self) {
+    for (_, timer) in self.timers.borrow_mut().drain() {
+      timer.cancellable.cancel();
+    }
+  }
+}
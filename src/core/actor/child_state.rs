@@ -32,23 +32,23 @@ impl ChildState {
 pub struct ChildRestartStats {
   child: ActorRef<AnyMessage>,
   max_nr_of_retries_count: i32,
-  restart_time_window_start_nanos: i64,
+  restart_time_window_start: Option<Instant>,
 }
 
 impl ChildRestartStats {
   pub fn new(child: ActorRef<AnyMessage>) -> Self {
-    Self::new_with(child, 0, 0)
+    Self::new_with(child, 0, None)
   }
 
   pub fn new_with(
     child: ActorRef<AnyMessage>,
     max_nr_of_retries_count: i32,
-    restart_time_window_start_nanos: i64,
+    restart_time_window_start: Option<Instant>,
   ) -> Self {
     Self {
       child,
       max_nr_of_retries_count,
-      restart_time_window_start_nanos,
+      restart_time_window_start,
     }
   }
 
@@ -56,7 +56,7 @@ impl ChildRestartStats {
     Self::new_with(
       child,
       self.max_nr_of_retries_count,
-      self.restart_time_window_start_nanos,
+      self.restart_time_window_start,
     )
   }
 
@@ -79,28 +79,39 @@ impl ChildRestartStats {
         self.max_nr_of_retries_count += 1;
         self.max_nr_of_retries_count <= retires
       }
-      (x, Some(window)) => self.retries_in_window_okay(if x.is_some() { x.unwrap() } else { 1 }, window),
+      (x, Some(window)) => self.retries_in_window_okay(x.unwrap_or(1), window),
       (None, _) => true,
     }
   }
 
   fn retries_in_window_okay(&mut self, retries: i32, window: i32) -> bool {
     let retries_done = self.max_nr_of_retries_count + 1;
-    let now = Instant::now().elapsed().as_nanos() as i64;
-    let window_start = if self.restart_time_window_start_nanos == 0 {
-      self.restart_time_window_start_nanos = now;
-      now
-    } else {
-      self.restart_time_window_start_nanos
-    };
-    let inside_window = (now - window_start) as u128 <= Duration::from_millis(window as u64).as_nanos();
+    let window_start = *self.restart_time_window_start.get_or_insert_with(Instant::now);
+    let inside_window = window_start.elapsed() <= Duration::from_millis(window as u64);
     if inside_window {
       self.max_nr_of_retries_count = retries_done;
       retries_done <= retries
     } else {
       self.max_nr_of_retries_count = 1;
-      self.restart_time_window_start_nanos = now;
+      self.restart_time_window_start = Some(Instant::now());
       true
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::core::actor::actor_path::ActorPath;
+
+  #[test]
+  fn test_retries_are_counted_within_the_time_window() {
+    let mut stats = ChildRestartStats::new(ActorRef::of_mock(ActorPath::from_string("test://test/child")));
+    assert!(stats.request_restart_permission((Some(2), Some(100))));
+    assert!(stats.request_restart_permission((Some(2), Some(100))));
+    assert!(!stats.request_restart_permission((Some(2), Some(100))));
+
+    std::thread::sleep(Duration::from_millis(150));
+    assert!(stats.request_restart_permission((Some(2), Some(100))));
+  }
+}
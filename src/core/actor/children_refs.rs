@@ -7,17 +7,46 @@ use crate::core::actor::actor_path::ActorPath;
 use crate::core::actor::actor_ref::ActorRef;
 use crate::core::actor::actor_ref::ActorRefBehavior;
 use crate::core::actor::child_state::{ChildRestartStats, ChildState};
+use crate::core::actor::ActorError;
 use base64_string_rs::Base64StringFactory;
 use rand::RngCore;
+use thiserror::Error;
 
 use crate::core::actor::props::Props;
 use crate::core::dispatch::any_message::AnyMessage;
 use crate::core::dispatch::message::Message;
 
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum InvalidActorNameError {
+  #[error("Actor name must not be empty")]
+  Empty,
+  #[error("Invalid actor name [{0}]")]
+  InvalidPathElement(String),
+  #[error("Actor name [{0}] is not unique")]
+  NotUnique(String),
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum ActorCreationError {
+  #[error(transparent)]
+  InvalidName(#[from] InvalidActorNameError),
+  #[error("Unknown dispatcher [{dispatcher}] for {path}")]
+  UnknownDispatcher { dispatcher: String, path: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SuspendReason {
+  UserRequest,
+  Recreation { cause: ActorError },
+  Creation,
+  Termination,
+}
+
 #[derive(Debug, Clone)]
 struct ChildrenRefsInner {
   children: BTreeMap<String, ChildState>,
   reserved_names: HashSet<String>,
+  suspend_reason: Option<SuspendReason>,
 }
 
 #[derive(Debug, Clone)]
@@ -31,6 +60,7 @@ impl ChildrenRefs {
       inner: Arc::new(Mutex::new(ChildrenRefsInner {
         children: BTreeMap::new(),
         reserved_names: HashSet::new(),
+        suspend_reason: None,
       })),
     }
   }
@@ -105,6 +135,53 @@ impl ChildrenRefs {
     inner.children.get(name).cloned()
   }
 
+  pub fn request_restart_permission(
+    &self,
+    child: &ActorRef<AnyMessage>,
+    retries_window: (Option<i32>, Option<i32>),
+  ) -> bool {
+    let mut inner = self.inner.lock().unwrap();
+    inner
+      .children
+      .values_mut()
+      .find_map(|state| match state {
+        ChildState::ChildRestartStats(stats) if stats.child_ref() == child => {
+          Some(stats.request_restart_permission(retries_window))
+        }
+        _ => None,
+      })
+      .unwrap_or(false)
+  }
+
+  /// Remembers why the children are being stopped.
+  ///
+  /// Returns `false` when there are no children to wait for, in which case the caller should proceed immediately.
+  pub fn set_children_termination_reason(&mut self, reason: SuspendReason) -> bool {
+    let mut inner = self.inner.lock().unwrap();
+    if inner.children.is_empty() {
+      false
+    } else {
+      inner.suspend_reason = Some(reason);
+      true
+    }
+  }
+
+  /// Returns `true` while the owning actor is waiting for its children to stop before terminating itself.
+  pub fn is_terminating(&self) -> bool {
+    let inner = self.inner.lock().unwrap();
+    inner.suspend_reason == Some(SuspendReason::Termination)
+  }
+
+  /// Takes the pending reason once the last child has gone.
+  pub fn take_children_termination_reason(&mut self) -> Option<SuspendReason> {
+    let mut inner = self.inner.lock().unwrap();
+    if inner.children.is_empty() {
+      inner.suspend_reason.take()
+    } else {
+      None
+    }
+  }
+
   pub fn reserve_child(&mut self, name: &str) -> bool {
     let mut inner = self.inner.lock().unwrap();
     if inner.reserved_names.contains(name) {
@@ -159,30 +236,52 @@ impl ChildrenRefs {
     self_ref: ActorRef<AnyMessage>,
     props: Rc<dyn Props<U>>,
     name: &str,
-  ) -> ActorRef<U> {
-    self.reserve_child(name);
-    let mut actor_ref = cell.new_child_actor(self_ref, props, name);
+  ) -> Result<ActorRef<U>, ActorCreationError> {
+    if !self.reserve_child(name) {
+      return Err(InvalidActorNameError::NotUnique(name.to_string()).into());
+    }
+    let mut actor_ref = cell.new_child_actor(self_ref, props, name).inspect_err(|_| {
+      self.un_reserve_child(name);
+    })?;
     self.init_child(actor_ref.clone().to_any(false), name).unwrap();
     actor_ref.start();
-    actor_ref
+    Ok(actor_ref)
   }
 
+  /// Creates a child named `name`, or fails if `name` is not a valid path element or is already taken, or if the
+  /// dispatcher the child is configured with does not exist.
   pub fn actor_with_name_of<U: Message>(
     &mut self,
     cell: ActorCell<AnyMessage>,
     self_ref: ActorRef<AnyMessage>,
     props: Rc<dyn Props<U>>,
     name: &str,
-  ) -> ActorRef<U> {
-    self.make_child(cell, self_ref, props, &Self::check_name(Some(name)))
+  ) -> Result<ActorRef<U>, ActorCreationError> {
+    let name = Self::check_name(name)?;
+    self.make_child(cell, self_ref, props, &name)
   }
 
+  /// Creates a child with a generated name.
+  ///
+  /// Panics if the child cannot be created; see `try_actor_of`.
   pub fn actor_of<U: Message>(
     &mut self,
     cell: ActorCell<AnyMessage>,
     self_ref: ActorRef<AnyMessage>,
     props: Rc<dyn Props<U>>,
   ) -> ActorRef<U> {
+    self
+      .try_actor_of(cell, self_ref, props)
+      .unwrap_or_else(|error| panic!("{}", error))
+  }
+
+  /// Creates a child with a generated name, or fails if the dispatcher the child is configured with does not exist.
+  pub fn try_actor_of<U: Message>(
+    &mut self,
+    cell: ActorCell<AnyMessage>,
+    self_ref: ActorRef<AnyMessage>,
+    props: Rc<dyn Props<U>>,
+  ) -> Result<ActorRef<U>, ActorCreationError> {
     self.make_child(cell, self_ref, props, &Self::random_name())
   }
 
@@ -194,14 +293,13 @@ impl ChildrenRefs {
     base64_string.to_string()
   }
 
-  fn check_name(name: Option<&str>) -> String {
-    match name {
-      None => panic!("actor name must not be empty"),
-      Some("") => panic!("actor name must not be empty"),
-      Some(n) => {
-        ActorPath::validate_path_element(n);
-        n.to_string()
-      }
+  fn check_name(name: &str) -> Result<String, InvalidActorNameError> {
+    if name.is_empty() {
+      Err(InvalidActorNameError::Empty)
+    } else if !ActorPath::is_valid_path_element(name) {
+      Err(InvalidActorNameError::InvalidPathElement(name.to_string()))
+    } else {
+      Ok(name.to_string())
     }
   }
 }
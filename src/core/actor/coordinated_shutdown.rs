@@ -0,0 +1,462 @@
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::future::join_all;
+use thiserror::Error;
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+
+pub const PHASE_BEFORE_SERVICE_UNBIND: &str = "before-service-unbind";
+pub const PHASE_SERVICE_UNBIND: &str = "service-unbind";
+pub const PHASE_SERVICE_REQUESTS_DONE: &str = "service-requests-done";
+pub const PHASE_SERVICE_STOP: &str = "service-stop";
+pub const PHASE_BEFORE_ACTOR_SYSTEM_TERMINATE: &str = "before-actor-system-terminate";
+pub const PHASE_ACTOR_SYSTEM_TERMINATE: &str = "actor-system-terminate";
+
+pub const DEFAULT_PHASE_TIMEOUT: Duration = Duration::from_secs(5);
+pub const DEFAULT_ACTOR_SYSTEM_TERMINATE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum CoordinatedShutdownError {
+  #[error("Unknown phase: {0}")]
+  UnknownPhase(String),
+  #[error("Phase {phase} did not complete within {timeout:?}")]
+  PhaseTimeout { phase: String, timeout: Duration },
+  #[error("Coordinated shutdown has already been started")]
+  AlreadyStarted,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShutdownReason {
+  Unknown,
+  ActorSystemTerminate,
+  Signal(String),
+  Custom(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Phase {
+  name: String,
+  timeout: Duration,
+  recover: bool,
+}
+
+impl Phase {
+  /// `recover` decides whether the shutdown moves on to the next phase when this one times out.
+  pub fn new(name: &str, timeout: Duration, recover: bool) -> Self {
+    Self {
+      name: name.to_string(),
+      timeout,
+      recover,
+    }
+  }
+
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  pub fn timeout(&self) -> Duration {
+    self.timeout
+  }
+
+  pub fn recover(&self) -> bool {
+    self.recover
+  }
+}
+
+pub fn default_phases() -> Vec<Phase> {
+  vec![
+    Phase::new(PHASE_BEFORE_SERVICE_UNBIND, DEFAULT_PHASE_TIMEOUT, true),
+    Phase::new(PHASE_SERVICE_UNBIND, DEFAULT_PHASE_TIMEOUT, true),
+    Phase::new(PHASE_SERVICE_REQUESTS_DONE, DEFAULT_PHASE_TIMEOUT, true),
+    Phase::new(PHASE_SERVICE_STOP, DEFAULT_PHASE_TIMEOUT, true),
+    Phase::new(PHASE_BEFORE_ACTOR_SYSTEM_TERMINATE, DEFAULT_PHASE_TIMEOUT, true),
+    Phase::new(
+      PHASE_ACTOR_SYSTEM_TERMINATE,
+      DEFAULT_ACTOR_SYSTEM_TERMINATE_TIMEOUT,
+      true,
+    ),
+  ]
+}
+
+type ShutdownTask = Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = ()> + Send>> + Send>;
+
+struct CoordinatedShutdownInner {
+  phases: Vec<Phase>,
+  tasks: HashMap<String, Vec<(String, ShutdownTask)>>,
+  reason: Option<ShutdownReason>,
+}
+
+/// Runs user tasks in named phases, one phase after another, when the actor system is shut down.
+#[derive(Clone)]
+pub struct CoordinatedShutdown {
+  inner: Arc<Mutex<CoordinatedShutdownInner>>,
+}
+
+impl Debug for CoordinatedShutdown {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let inner = self.inner.lock().unwrap();
+    f.debug_struct("CoordinatedShutdown")
+      .field("phases", &inner.phases)
+      .field("reason", &inner.reason)
+      .finish()
+  }
+}
+
+impl Default for CoordinatedShutdown {
+  fn default() -> Self {
+    Self::new(default_phases())
+  }
+}
+
+impl CoordinatedShutdown {
+  /// Creates a shutdown whose phases run in the given order.
+  pub fn new(phases: Vec<Phase>) -> Self {
+    Self {
+      inner: Arc::new(Mutex::new(CoordinatedShutdownInner {
+        phases,
+        tasks: HashMap::new(),
+        reason: None,
+      })),
+    }
+  }
+
+  pub fn phases(&self) -> Vec<Phase> {
+    let inner = self.inner.lock().unwrap();
+    inner.phases.clone()
+  }
+
+  pub fn set_phase_timeout(&self, phase: &str, timeout: Duration) -> Result<(), CoordinatedShutdownError> {
+    let mut inner = self.inner.lock().unwrap();
+    match inner.phases.iter_mut().find(|p| p.name == phase) {
+      Some(p) => {
+        p.timeout = timeout;
+        Ok(())
+      }
+      None => Err(CoordinatedShutdownError::UnknownPhase(phase.to_string())),
+    }
+  }
+
+  pub fn phase_timeout(&self, phase: &str) -> Option<Duration> {
+    let inner = self.inner.lock().unwrap();
+    inner.phases.iter().find(|p| p.name == phase).map(|p| p.timeout)
+  }
+
+  /// Adds a task to `phase`. Tasks of the same phase run concurrently.
+  pub fn add_task<F, Fut>(&self, phase: &str, task_name: &str, task: F) -> Result<(), CoordinatedShutdownError>
+  where
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static, {
+    let mut inner = self.inner.lock().unwrap();
+    if inner.reason.is_some() {
+      return Err(CoordinatedShutdownError::AlreadyStarted);
+    }
+    if !inner.phases.iter().any(|p| p.name == phase) {
+      return Err(CoordinatedShutdownError::UnknownPhase(phase.to_string()));
+    }
+    let task: ShutdownTask = Box::new(move || Box::pin(task()));
+    inner
+      .tasks
+      .entry(phase.to_string())
+      .or_default()
+      .push((task_name.to_string(), task));
+    Ok(())
+  }
+
+  pub fn shutdown_reason(&self) -> Option<ShutdownReason> {
+    let inner = self.inner.lock().unwrap();
+    inner.reason.clone()
+  }
+
+  /// Starts the shutdown and returns a future that completes after the last phase.
+  ///
+  /// Only the first call runs the phases; later calls fail with `CoordinatedShutdownError::AlreadyStarted`.
+  pub fn run(&self, reason: ShutdownReason) -> impl Future<Output = Result<(), CoordinatedShutdownError>> + Send {
+    let phases = {
+      let mut inner = self.inner.lock().unwrap();
+      if inner.reason.is_some() {
+        Err(CoordinatedShutdownError::AlreadyStarted)
+      } else {
+        log::info!("run: reason = {:?}", reason);
+        inner.reason = Some(reason);
+        let mut tasks = std::mem::take(&mut inner.tasks);
+        Ok(
+          inner
+            .phases
+            .iter()
+            .map(|phase| (phase.clone(), tasks.remove(&phase.name).unwrap_or_default()))
+            .collect::<Vec<_>>(),
+        )
+      }
+    };
+    async move {
+      for (phase, tasks) in phases? {
+        let task_names = tasks.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>();
+        log::debug!("run: phase = {}, tasks = {:?}", phase.name, task_names);
+        let futures = tasks.into_iter().map(|(_, task)| task());
+        if tokio::time::timeout(phase.timeout, join_all(futures)).await.is_err() {
+          if phase.recover {
+            log::warn!("run: phase {} timed out after {:?}", phase.name, phase.timeout);
+          } else {
+            return Err(CoordinatedShutdownError::PhaseTimeout {
+              phase: phase.name,
+              timeout: phase.timeout,
+            });
+          }
+        }
+      }
+      Ok(())
+    }
+  }
+
+  /// Runs the shutdown on `runtime` as soon as the process receives SIGTERM or SIGINT.
+  pub fn run_on_signals(&self, runtime: &Handle) -> JoinHandle<()> {
+    self.run_when(runtime, wait_for_signal())
+  }
+
+  /// Runs the shutdown on `runtime` once `trigger` resolves, with the name of the signal it yields as the reason.
+  pub fn run_when<T>(&self, runtime: &Handle, trigger: T) -> JoinHandle<()>
+  where
+    T: Future<Output = String> + Send + 'static, {
+    let cloned_self = self.clone();
+    runtime.spawn(async move {
+      let signal = trigger.await;
+      log::info!("run_when: received {}", signal);
+      if let Err(error) = cloned_self.run(ShutdownReason::Signal(signal)).await {
+        log::error!("run_when: {}", error);
+      }
+    })
+  }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() -> String {
+  use tokio::signal::unix::{signal, SignalKind};
+  let mut sigterm = signal(SignalKind::terminate()).unwrap();
+  tokio::select! {
+    _ = tokio::signal::ctrl_c() => "SIGINT".to_string(),
+    _ = sigterm.recv() => "SIGTERM".to_string(),
+  }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() -> String {
+  tokio::signal::ctrl_c().await.unwrap();
+  "SIGINT".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::core::actor::actor_ref::ActorRefBehavior;
+  use crate::core::actor::actor_ref_provider::ActorRefProvider;
+  use crate::core::actor::actor_system::actor_system_builder::ActorSystemBuilder;
+  use crate::core::actor::test_kit::{block_on, init_logger, start_system, EventActor, EventProbe, EVENT_TIMEOUT};
+  use tokio::runtime;
+
+  fn record(events: &Arc<Mutex<Vec<String>>>, event: &str) -> impl Future<Output = ()> + Send + 'static {
+    let events = events.clone();
+    let event = event.to_string();
+    async move {
+      events.lock().unwrap().push(event);
+    }
+  }
+
+  #[test]
+  fn test_phases_run_in_order() {
+    init_logger();
+    let coordinated_shutdown = CoordinatedShutdown::default();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let e = events.clone();
+    coordinated_shutdown
+      .add_task(PHASE_ACTOR_SYSTEM_TERMINATE, "terminate", move || record(&e, "terminate"))
+      .unwrap();
+    let e = events.clone();
+    coordinated_shutdown
+      .add_task(PHASE_BEFORE_SERVICE_UNBIND, "unbind", move || record(&e, "unbind"))
+      .unwrap();
+    let e = events.clone();
+    coordinated_shutdown
+      .add_task(PHASE_SERVICE_STOP, "stop", move || record(&e, "stop"))
+      .unwrap();
+
+    let result = block_on(coordinated_shutdown.run(ShutdownReason::Unknown));
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(*events.lock().unwrap(), vec!["unbind", "stop", "terminate"]);
+    assert_eq!(coordinated_shutdown.shutdown_reason(), Some(ShutdownReason::Unknown));
+  }
+
+  #[test]
+  fn test_phase_timeout_with_recover_continues() {
+    init_logger();
+    let coordinated_shutdown = CoordinatedShutdown::new(vec![
+      Phase::new("slow", Duration::from_millis(100), true),
+      Phase::new("last", Duration::from_millis(100), false),
+    ]);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    coordinated_shutdown
+      .add_task("slow", "sleep", || tokio::time::sleep(Duration::from_secs(5)))
+      .unwrap();
+    let e = events.clone();
+    coordinated_shutdown
+      .add_task("last", "last", move || record(&e, "last"))
+      .unwrap();
+
+    let result = block_on(coordinated_shutdown.run(ShutdownReason::Unknown));
+
+    assert_eq!(result, Ok(()));
+    assert_eq!(*events.lock().unwrap(), vec!["last"]);
+  }
+
+  #[test]
+  fn test_phase_timeout_without_recover_fails() {
+    init_logger();
+    let coordinated_shutdown = CoordinatedShutdown::new(vec![
+      Phase::new("slow", Duration::from_millis(100), false),
+      Phase::new("last", Duration::from_millis(100), false),
+    ]);
+    let events = Arc::new(Mutex::new(Vec::new()));
+    coordinated_shutdown
+      .add_task("slow", "sleep", || tokio::time::sleep(Duration::from_secs(5)))
+      .unwrap();
+    let e = events.clone();
+    coordinated_shutdown
+      .add_task("last", "last", move || record(&e, "last"))
+      .unwrap();
+
+    let result = block_on(coordinated_shutdown.run(ShutdownReason::Unknown));
+
+    assert_eq!(
+      result,
+      Err(CoordinatedShutdownError::PhaseTimeout {
+        phase: "slow".to_string(),
+        timeout: Duration::from_millis(100),
+      })
+    );
+    assert!(events.lock().unwrap().is_empty());
+  }
+
+  #[test]
+  fn test_run_when_starts_once_triggered() {
+    init_logger();
+    let runtime = runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+    let coordinated_shutdown = CoordinatedShutdown::default();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let e = events.clone();
+    coordinated_shutdown
+      .add_task(PHASE_SERVICE_STOP, "stop", move || record(&e, "stop"))
+      .unwrap();
+
+    let (trigger_tx, trigger_rx) = tokio::sync::oneshot::channel::<String>();
+    let handle = coordinated_shutdown.run_when(runtime.handle(), async move { trigger_rx.await.unwrap() });
+    std::thread::sleep(Duration::from_millis(100));
+    assert!(events.lock().unwrap().is_empty());
+    assert_eq!(coordinated_shutdown.shutdown_reason(), None);
+
+    trigger_tx.send("SIGTERM".to_string()).unwrap();
+    runtime.block_on(handle).unwrap();
+    assert_eq!(*events.lock().unwrap(), vec!["stop"]);
+    assert_eq!(
+      coordinated_shutdown.shutdown_reason(),
+      Some(ShutdownReason::Signal("SIGTERM".to_string()))
+    );
+  }
+
+  #[test]
+  fn test_unknown_phase_and_second_run_are_rejected() {
+    init_logger();
+    let coordinated_shutdown = CoordinatedShutdown::default();
+    assert_eq!(
+      coordinated_shutdown.add_task("no-such-phase", "task", || async {}),
+      Err(CoordinatedShutdownError::UnknownPhase("no-such-phase".to_string()))
+    );
+
+    assert_eq!(block_on(coordinated_shutdown.run(ShutdownReason::Unknown)), Ok(()));
+    assert_eq!(
+      block_on(coordinated_shutdown.run(ShutdownReason::Unknown)),
+      Err(CoordinatedShutdownError::AlreadyStarted)
+    );
+    assert_eq!(
+      coordinated_shutdown.add_task(PHASE_SERVICE_STOP, "late", || async {}),
+      Err(CoordinatedShutdownError::AlreadyStarted)
+    );
+  }
+
+  #[test]
+  fn test_terminate_runs_coordinated_shutdown_phases() {
+    let probe = EventProbe::new();
+    let (actor_system, mut main_ref) = start_system(EventActor::props("parent", probe.events_tx(), None));
+    main_ref.tell("start".to_string());
+    probe.wait_for("parent:received:start");
+
+    let events_tx = probe.events_tx();
+    actor_system
+      .coordinated_shutdown()
+      .add_task(PHASE_SERVICE_STOP, "service", move || {
+        events_tx.send("service:stopped".to_string()).unwrap();
+        async {}
+      })
+      .unwrap();
+
+    assert_eq!(actor_system.terminate(), Ok(()));
+    assert_eq!(probe.drain(), vec!["service:stopped", "parent:post_stop"]);
+  }
+
+  #[test]
+  fn test_terminate_async_runs_inside_a_runtime() {
+    init_logger();
+    let runtime = runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+    let probe = EventProbe::new();
+    let actor_system = ActorSystemBuilder::new("test")
+      .with_runtime_handle(runtime.handle().clone())
+      .with_guardian_props(EventActor::props("parent", probe.events_tx(), None))
+      .build()
+      .unwrap();
+
+    let result = runtime.block_on(async { actor_system.terminate_async().await });
+    assert_eq!(result, Ok(()));
+    probe.wait_for("parent:post_stop");
+  }
+
+  #[test]
+  fn test_coordinated_shutdown_runs_when_triggered() {
+    let probe = EventProbe::new();
+    let (actor_system, _) = start_system(EventActor::props("parent", probe.events_tx(), None));
+    let (trigger_tx, trigger_rx) = tokio::sync::oneshot::channel::<String>();
+    actor_system.run_coordinated_shutdown_when(async move { trigger_rx.await.unwrap() });
+    std::thread::sleep(Duration::from_millis(100));
+    assert_eq!(actor_system.coordinated_shutdown().shutdown_reason(), None);
+
+    trigger_tx.send("SIGTERM".to_string()).unwrap();
+    actor_system.when_terminate();
+    probe.wait_for("parent:post_stop");
+    assert_eq!(
+      actor_system.coordinated_shutdown().shutdown_reason(),
+      Some(ShutdownReason::Signal("SIGTERM".to_string()))
+    );
+    assert!(actor_system.provider().root_guardian().actor_cell().unwrap().is_terminated());
+  }
+
+  #[test]
+  fn test_when_terminate_stops_the_root_guardian_after_main() {
+    let probe = EventProbe::new();
+    let (actor_system, mut parent_ref) = start_system(EventActor::props("parent", probe.events_tx(), None));
+    let stopper = std::thread::spawn(move || {
+      std::thread::sleep(Duration::from_millis(100));
+      block_on(parent_ref.graceful_stop(EVENT_TIMEOUT, None))
+    });
+
+    actor_system.when_terminate();
+    assert_eq!(stopper.join().unwrap(), Ok(()));
+    assert_eq!(
+      actor_system.coordinated_shutdown().shutdown_reason(),
+      Some(ShutdownReason::ActorSystemTerminate)
+    );
+    assert!(actor_system.provider().root_guardian().actor_cell().unwrap().is_terminated());
+  }
+}
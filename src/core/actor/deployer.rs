@@ -0,0 +1,206 @@
+use crate::core::actor::actor_path::{ActorPath, ActorPathBehavior};
+use crate::core::actor::actor_selection::matches_pattern;
+use crate::core::dispatch::mailbox::mailbox_type::MailboxType;
+use crate::core::routing::RouterConfig;
+use std::cmp::Reverse;
+use std::sync::Arc;
+
+/// Overrides for the actors created at the paths matching `path`, e.g. `/user/ingest/*`.
+///
+/// Each element of `path` may contain the wildcards `*` and `?`; `path` has to match the whole actor path.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deploy {
+  pub path: String,
+  pub mailbox_type: Option<MailboxType>,
+  pub dispatcher: Option<String>,
+  pub router_config: Option<RouterConfig>,
+}
+
+impl Deploy {
+  pub fn new(path: &str) -> Self {
+    Self {
+      path: path.to_string(),
+      mailbox_type: None,
+      dispatcher: None,
+      router_config: None,
+    }
+  }
+
+  pub fn with_mailbox_type(mut self, mailbox_type: MailboxType) -> Self {
+    self.mailbox_type = Some(mailbox_type);
+    self
+  }
+
+  pub fn with_dispatcher(mut self, dispatcher: &str) -> Self {
+    self.dispatcher = Some(dispatcher.to_string());
+    self
+  }
+
+  pub fn with_router_config(mut self, router_config: RouterConfig) -> Self {
+    self.router_config = Some(router_config);
+    self
+  }
+
+  fn elements(&self) -> Vec<&str> {
+    self.path.split('/').filter(|element| !element.is_empty()).collect()
+  }
+
+  fn wildcards(&self) -> usize {
+    self.path.matches(['*', '?']).count()
+  }
+
+  fn literal_prefix_len(&self) -> usize {
+    self.path.find(['*', '?']).unwrap_or(self.path.len())
+  }
+
+  fn matches(&self, elements: &[String]) -> bool {
+    let deploy_elements = self.elements();
+    deploy_elements.len() == elements.len()
+      && deploy_elements.iter().zip(elements).all(|(pattern, element)| {
+        matches_pattern(
+          &pattern.chars().collect::<Vec<_>>(),
+          &element.chars().collect::<Vec<_>>(),
+        )
+      })
+  }
+}
+
+/// Looks up the deployment overrides of the actors being created.
+#[derive(Debug, Clone, Default)]
+pub struct Deployer {
+  deployments: Arc<Vec<Deploy>>,
+}
+
+impl Deployer {
+  pub fn new(deployments: Vec<Deploy>) -> Self {
+    Self {
+      deployments: Arc::new(deployments),
+    }
+  }
+
+  pub fn deployments(&self) -> &[Deploy] {
+    &self.deployments
+  }
+
+  /// Returns the most specific deployment for `path`: an exact path first, then the pattern with the fewest
+  /// wildcards, then the one with the longest literal prefix.
+  pub fn lookup(&self, path: &ActorPath) -> Option<Deploy> {
+    let elements = path.elements();
+    self
+      .deployments
+      .iter()
+      .filter(|deploy| deploy.matches(&elements))
+      .min_by_key(|deploy| (deploy.wildcards(), Reverse(deploy.literal_prefix_len())))
+      .cloned()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::core::actor::actor_cell::ActorCellBehavior;
+  use crate::core::actor::actor_ref::{ActorRefBehavior, AskError};
+  use crate::core::actor::actor_system::actor_system_builder::ActorSystemBuilder;
+  use crate::core::actor::actor_system::actor_system_settings::{ActorSystemSettings, DispatcherSettings};
+  use crate::core::actor::address::Address;
+  use crate::core::actor::test_kit::{init_logger, EventProbe, ReportingActor, SpawningActor, EVENT_TIMEOUT};
+  use crate::core::dispatch::any_message::AnyMessage;
+
+  fn path_of(elements: &[&str]) -> ActorPath {
+    elements
+      .iter()
+      .fold(ActorPath::of_root(Address::new("tcp", "test")), |path, element| {
+        path.with_child(element)
+      })
+  }
+
+  #[test]
+  fn test_lookup_prefers_exact_paths() {
+    let deployer = Deployer::new(vec![
+      Deploy::new("/user/ingest/*").with_dispatcher("ingest"),
+      Deploy::new("/user/ingest/worker-?").with_dispatcher("worker"),
+      Deploy::new("/user/ingest/special").with_mailbox_type(MailboxType::of_unbounded_deque()),
+    ]);
+
+    let deploy = deployer.lookup(&path_of(&["user", "ingest", "worker-1"])).unwrap();
+    assert_eq!(deploy.dispatcher, Some("worker".to_string()));
+    let deploy = deployer.lookup(&path_of(&["user", "ingest", "reader"])).unwrap();
+    assert_eq!(deploy.dispatcher, Some("ingest".to_string()));
+    let deploy = deployer.lookup(&path_of(&["user", "ingest", "special"])).unwrap();
+    assert_eq!(deploy.path, "/user/ingest/special");
+    assert_eq!(deployer.lookup(&path_of(&["user", "ingest"])), None);
+    assert_eq!(deployer.lookup(&path_of(&["user", "ingest", "a", "b"])), None);
+    assert_eq!(deployer.lookup(&path_of(&["system", "ingest", "a"])), None);
+  }
+
+  #[test]
+  fn test_lookup_does_not_depend_on_the_order_of_deployments() {
+    let deployments = vec![
+      Deploy::new("/user/*/*").with_dispatcher("any"),
+      Deploy::new("/user/ingest/*").with_dispatcher("ingest"),
+      Deploy::new("/user/ingest/worker-*").with_dispatcher("worker"),
+      Deploy::new("/user/ingest/worker-1").with_dispatcher("first-worker"),
+    ];
+    let reversed = deployments.iter().rev().cloned().collect::<Vec<_>>();
+
+    for deployer in [Deployer::new(deployments), Deployer::new(reversed)] {
+      let lookup = |elements: &[&str]| deployer.lookup(&path_of(elements)).and_then(|deploy| deploy.dispatcher);
+      assert_eq!(lookup(&["user", "ingest", "worker-1"]), Some("first-worker".to_string()));
+      assert_eq!(lookup(&["user", "ingest", "worker-2"]), Some("worker".to_string()));
+      assert_eq!(lookup(&["user", "ingest", "reader"]), Some("ingest".to_string()));
+      assert_eq!(lookup(&["user", "egress", "reader"]), Some("any".to_string()));
+    }
+  }
+
+  #[test]
+  fn test_deployments_override_actors_created_at_matching_paths() {
+    init_logger();
+    let probe = EventProbe::new();
+    let settings = ActorSystemSettings {
+      dispatchers: vec![DispatcherSettings {
+        name: "bulk-dispatcher".to_string(),
+        throughput: 7,
+        throughput_deadline: None,
+      }],
+      deployments: vec![
+        Deploy::new("/user/orders/pool-*").with_router_config(RouterConfig::of_round_robin_pool(2)),
+        Deploy::new("/user/orders/all").with_router_config(RouterConfig::of_broadcast_pool(3)),
+        Deploy::new("/user/orders/bulk")
+          .with_mailbox_type(MailboxType::of_unbounded_deque())
+          .with_dispatcher("bulk-dispatcher"),
+      ],
+      ..ActorSystemSettings::default()
+    };
+    let actor_system = ActorSystemBuilder::new("test")
+      .with_guardian_props(ReportingActor::props(probe.events_tx()))
+      .with_settings(settings)
+      .build()
+      .unwrap();
+    let orders_props = SpawningActor::props(ReportingActor::props(probe.events_tx()));
+    let mut orders_ref = actor_system.actor_of(orders_props, "orders");
+    for name in ["pool-a", "all", "bulk"] {
+      let reply: Result<String, AskError> = orders_ref.ask_blocking(|_| name.to_string(), EVENT_TIMEOUT);
+      assert_eq!(reply, Ok(format!("spawned:{}", name)));
+    }
+
+    let children = orders_ref.actor_cell().unwrap().children();
+    let mut pool_ref = children.get_child_ref("pool-a").unwrap();
+    for n in 1..=4 {
+      pool_ref.tell(AnyMessage::new(format!("job-{}", n)));
+    }
+    assert_eq!(
+      probe.next_n_sorted(4),
+      vec!["routee-1:job-1", "routee-1:job-3", "routee-2:job-2", "routee-2:job-4"]
+    );
+    children.get_child_ref("all").unwrap().tell(AnyMessage::new("news".to_string()));
+    assert_eq!(probe.next_n_sorted(3), vec!["routee-1:news", "routee-2:news", "routee-3:news"]);
+
+    let mut bulk_ref = children.get_child_ref("bulk").unwrap();
+    let bulk_mailbox = bulk_ref.actor_cell().unwrap().mailbox();
+    assert_eq!(bulk_mailbox.mailbox_type(), MailboxType::of_unbounded_deque());
+    assert_eq!(bulk_mailbox.throughput(), 7);
+    bulk_ref.tell(AnyMessage::new("load".to_string()));
+    assert_eq!(probe.next(), "bulk:load");
+    assert_eq!(orders_ref.actor_cell().unwrap().mailbox().throughput(), 1);
+  }
+}
@@ -1,14 +1,75 @@
+use crate::core::actor::supervisor_strategy::SupervisorStrategy;
 use crate::core::actor::{ActorBehavior, AnyMessageActorWrapper, MockActorMutable};
 use crate::core::dispatch::any_message::AnyMessage;
+use crate::core::dispatch::mailbox::mailbox_type::MailboxType;
 use crate::core::dispatch::message::Message;
+use crate::core::routing::RouterConfig;
 use std::cell::RefCell;
 use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::rc::Rc;
 
 pub trait Props<Msg: Message>: Debug {
-  type Actor: ActorBehavior<Msg>;
-  fn new_actor(&self) -> Self::Actor;
+  fn new_actor(&self) -> Rc<RefCell<dyn ActorBehavior<Msg>>>;
+
+  /// The mailbox type of the actor, or `None` to use the default mailbox type of the actor system.
+  fn mailbox_type(&self) -> Option<MailboxType> {
+    None
+  }
+
+  /// The name of a dispatcher registered in the actor system settings, or `None` to use the one of the parent.
+  fn dispatcher(&self) -> Option<String> {
+    None
+  }
+
+  /// The strategy the actor supervises its children with, or `None` to ask `ActorBehavior::supervisor_strategy`.
+  fn supervisor_strategy(&self) -> Option<SupervisorStrategy> {
+    None
+  }
+
+  /// Makes the actor a pool router whose routees are created from these props.
+  fn router_config(&self) -> Option<RouterConfig> {
+    None
+  }
+
+  /// Whether the actor is a routee of a pool router, which a deployment never turns into a router itself.
+  fn is_routee(&self) -> bool {
+    false
+  }
+}
+
+/// The optional settings that props carry besides the way they create the actor.
+#[derive(Debug, Clone, Default)]
+pub struct PropsSettings {
+  pub mailbox_type: Option<MailboxType>,
+  pub dispatcher: Option<String>,
+  pub supervisor_strategy: Option<SupervisorStrategy>,
+  pub router_config: Option<RouterConfig>,
+}
+
+/// The builders of the props that carry `PropsSettings`.
+pub trait PropsSettingsBehavior: Sized {
+  fn settings_mut(&mut self) -> &mut PropsSettings;
+
+  fn with_mailbox(mut self, mailbox_type: MailboxType) -> Self {
+    self.settings_mut().mailbox_type = Some(mailbox_type);
+    self
+  }
+
+  fn with_dispatcher(mut self, dispatcher: &str) -> Self {
+    self.settings_mut().dispatcher = Some(dispatcher.to_string());
+    self
+  }
+
+  fn with_supervisor_strategy(mut self, supervisor_strategy: SupervisorStrategy) -> Self {
+    self.settings_mut().supervisor_strategy = Some(supervisor_strategy);
+    self
+  }
+
+  fn with_router(mut self, router_config: RouterConfig) -> Self {
+    self.settings_mut().router_config = Some(router_config);
+    self
+  }
 }
 
 #[derive(Debug, Clone)]
@@ -23,67 +84,117 @@ impl<Msg: Message> MockProps<Msg> {
 }
 
 impl<Msg: Message> Props<Msg> for MockProps<Msg> {
-  type Actor = MockActorMutable<Msg>;
-
-  fn new_actor(&self) -> Self::Actor {
-    MockActorMutable { p: PhantomData }
+  fn new_actor(&self) -> Rc<RefCell<dyn ActorBehavior<Msg>>> {
+    Rc::new(RefCell::new(MockActorMutable { p: PhantomData }))
   }
 }
 
 #[derive(Debug, Clone)]
 pub struct AnyProps<Msg: Message> {
-  pub underlying: Rc<dyn Props<Msg, Actor = AnyMessageActorWrapper<Msg>>>,
+  pub underlying: Rc<dyn Props<Msg>>,
 }
 
 impl<Msg: Message> AnyProps<Msg> {
-  pub fn new(underlying: Rc<dyn Props<Msg, Actor = AnyMessageActorWrapper<Msg>>>) -> Self {
+  pub fn new(underlying: Rc<dyn Props<Msg>>) -> Self {
     Self { underlying }
   }
 }
 
 impl<Msg: Message> Props<AnyMessage> for AnyProps<Msg> {
-  type Actor = AnyMessageActorWrapper<Msg>;
+  fn new_actor(&self) -> Rc<RefCell<dyn ActorBehavior<AnyMessage>>> {
+    Rc::new(RefCell::new(AnyMessageActorWrapper::new(self.underlying.new_actor())))
+  }
+
+  fn mailbox_type(&self) -> Option<MailboxType> {
+    self.underlying.mailbox_type()
+  }
+
+  fn dispatcher(&self) -> Option<String> {
+    self.underlying.dispatcher()
+  }
 
-  fn new_actor(&self) -> Self::Actor {
-    AnyMessageActorWrapper::new(self.underlying.new_actor())
+  fn supervisor_strategy(&self) -> Option<SupervisorStrategy> {
+    self.underlying.supervisor_strategy()
+  }
+
+  fn router_config(&self) -> Option<RouterConfig> {
+    self.underlying.router_config()
+  }
+
+  fn is_routee(&self) -> bool {
+    self.underlying.is_routee()
   }
   // fn new_actor<A: ActorBehavior<Msg>>(&self) -> A {
   //   AnyMessageActorWrapper::new(self.underlying.new_actor())
   // }
 }
 
+/// Creates every actor as a clone of `actor`.
 #[derive(Debug, Clone)]
 pub struct SingletonProps<Msg: Message, A: ActorBehavior<Msg> + Clone> {
+  p: PhantomData<Msg>,
   actor: A,
+  settings: PropsSettings,
 }
 
 impl<Msg: Message, A: ActorBehavior<Msg> + Clone> SingletonProps<Msg, A> {
   pub fn new(actor: A) -> Self {
-    Self { actor }
+    Self {
+      p: PhantomData,
+      actor,
+      settings: PropsSettings::default(),
+    }
   }
 }
 
-impl<Msg: Message, A: ActorBehavior<Msg> + Clone> Props<Msg> for SingletonProps<Msg, A> {
-  fn new_actor<X: ActorBehavior<Msg>>(&self) -> X {
-    self.actor.clone()
+impl<Msg: Message, A: ActorBehavior<Msg> + Clone> PropsSettingsBehavior for SingletonProps<Msg, A> {
+  fn settings_mut(&mut self) -> &mut PropsSettings {
+    &mut self.settings
   }
 }
 
+impl<Msg: Message, A: ActorBehavior<Msg> + Clone + 'static> Props<Msg> for SingletonProps<Msg, A> {
+  fn new_actor(&self) -> Rc<RefCell<dyn ActorBehavior<Msg>>> {
+    Rc::new(RefCell::new(self.actor.clone()))
+  }
+
+  fn mailbox_type(&self) -> Option<MailboxType> {
+    self.settings.mailbox_type.clone()
+  }
+
+  fn dispatcher(&self) -> Option<String> {
+    self.settings.dispatcher.clone()
+  }
+
+  fn supervisor_strategy(&self) -> Option<SupervisorStrategy> {
+    self.settings.supervisor_strategy.clone()
+  }
+
+  fn router_config(&self) -> Option<RouterConfig> {
+    self.settings.router_config.clone()
+  }
+}
+
+/// Creates every actor by calling `actor_f`.
 pub struct FunctionProps<Msg: Message> {
   actor_f: Rc<dyn Fn() -> Rc<RefCell<dyn ActorBehavior<Msg>>>>,
+  settings: PropsSettings,
 }
 
 impl<Msg: Message> Clone for FunctionProps<Msg> {
   fn clone(&self) -> Self {
     Self {
       actor_f: self.actor_f.clone(),
+      settings: self.settings.clone(),
     }
   }
 }
 
 impl<Msg: Message> Debug for FunctionProps<Msg> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-    f.debug_struct("FunctionProps").finish()
+    f.debug_struct("FunctionProps")
+      .field("settings", &self.settings)
+      .finish()
   }
 }
 
@@ -93,12 +204,124 @@ impl<Msg: Message> FunctionProps<Msg> {
     F: Fn() -> Rc<RefCell<dyn ActorBehavior<Msg>>> + 'static, {
     Self {
       actor_f: Rc::new(actor_f),
+      settings: PropsSettings::default(),
     }
   }
+
+  /// Passes `args` to `actor_f` whenever an actor is created, e.g. on every restart.
+  pub fn new_with_args<Args, F>(args: Args, actor_f: F) -> Self
+  where
+    Args: 'static,
+    F: Fn(&Args) -> Rc<RefCell<dyn ActorBehavior<Msg>>> + 'static, {
+    Self::new(move || actor_f(&args))
+  }
+
+  /// Like `new`, but `actor_f` returns the actor itself.
+  pub fn of_actor<A, F>(actor_f: F) -> Self
+  where
+    A: ActorBehavior<Msg> + 'static,
+    F: Fn() -> A + 'static, {
+    Self::new(move || Rc::new(RefCell::new(actor_f())))
+  }
+
+  /// Like `new_with_args`, but `actor_f` returns the actor itself.
+  pub fn of_actor_with_args<Args, A, F>(args: Args, actor_f: F) -> Self
+  where
+    Args: 'static,
+    A: ActorBehavior<Msg> + 'static,
+    F: Fn(&Args) -> A + 'static, {
+    Self::new(move || Rc::new(RefCell::new(actor_f(&args))))
+  }
+}
+
+impl<Msg: Message> PropsSettingsBehavior for FunctionProps<Msg> {
+  fn settings_mut(&mut self) -> &mut PropsSettings {
+    &mut self.settings
+  }
 }
 
 impl<Msg: Message> Props<Msg> for FunctionProps<Msg> {
   fn new_actor(&self) -> Rc<RefCell<dyn ActorBehavior<Msg>>> {
     (*self.actor_f.clone())()
   }
+
+  fn mailbox_type(&self) -> Option<MailboxType> {
+    self.settings.mailbox_type.clone()
+  }
+
+  fn dispatcher(&self) -> Option<String> {
+    self.settings.dispatcher.clone()
+  }
+
+  fn supervisor_strategy(&self) -> Option<SupervisorStrategy> {
+    self.settings.supervisor_strategy.clone()
+  }
+
+  fn router_config(&self) -> Option<RouterConfig> {
+    self.settings.router_config.clone()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::core::actor::actor_cell::ActorCellBehavior;
+  use crate::core::actor::actor_ref::ActorRefBehavior;
+  use crate::core::actor::actor_system::actor_system_builder::ActorSystemBuilder;
+  use crate::core::actor::actor_system::actor_system_settings::{ActorSystemSettings, DispatcherSettings};
+  use crate::core::actor::supervisor_strategy::Directive;
+  use crate::core::actor::test_kit::{init_logger, EventProbe, FailingChildActor, ReportingActor, SupervisingActor};
+
+  #[test]
+  fn test_props_settings_are_applied_when_creating_actors() {
+    init_logger();
+    let probe = EventProbe::new();
+    let terminated = EventProbe::new();
+    let settings = ActorSystemSettings {
+      dispatchers: vec![DispatcherSettings {
+        name: "bulk-dispatcher".to_string(),
+        throughput: 7,
+        throughput_deadline: None,
+      }],
+      ..ActorSystemSettings::default()
+    };
+    // The strategy of the props wins over the one of the actor, which would restart the child.
+    let main_props = FunctionProps::of_actor_with_args(terminated.events_tx(), |terminated_tx| {
+      SupervisingActor::new(
+        Rc::new(FunctionProps::of_actor(|| FailingChildActor)),
+        SupervisorStrategy::of_one_for_one(None, None, |_| Directive::Restart),
+        terminated_tx.clone(),
+      )
+    })
+    .with_supervisor_strategy(SupervisorStrategy::of_one_for_one(None, None, |_| Directive::Stop));
+    let actor_system = ActorSystemBuilder::new("test")
+      .with_guardian_props(Rc::new(main_props))
+      .with_settings(settings)
+      .build()
+      .unwrap();
+    actor_system.guardian_ref().tell("boom".to_string());
+    assert_eq!(terminated.next(), "failing-child");
+
+    let pool_props = FunctionProps::of_actor_with_args(probe.events_tx(), |events_tx| {
+      ReportingActor::new(events_tx.clone())
+    })
+    .with_mailbox(MailboxType::of_unbounded_deque())
+    .with_dispatcher("bulk-dispatcher")
+    .with_router(RouterConfig::of_round_robin_pool(2));
+    let mut pool_ref = actor_system.actor_of(Rc::new(pool_props), "pool");
+    for n in 1..=4 {
+      pool_ref.tell(format!("job-{}", n));
+    }
+    assert_eq!(
+      probe.next_n_sorted(4),
+      vec!["routee-1:job-1", "routee-1:job-3", "routee-2:job-2", "routee-2:job-4"]
+    );
+    let routees = pool_ref.actor_cell().unwrap().children().children();
+    assert_eq!(routees.len(), 2);
+    for routee_ref in routees {
+      let routee_mailbox = routee_ref.actor_cell().unwrap().mailbox();
+      assert_eq!(routee_mailbox.mailbox_type(), MailboxType::of_unbounded_deque());
+      assert_eq!(routee_mailbox.throughput(), 7);
+    }
+  }
 }
@@ -9,7 +9,8 @@ use tokio::{runtime, task};
 use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior};
 use crate::core::dispatch::message::Message;
 use thiserror::Error;
-use tokio::runtime::Runtime;
+use tokio::runtime::Handle;
+use tokio::time::{Instant, Interval};
 
 #[derive(Error, Debug)]
 pub enum CancellableError {
@@ -62,6 +63,16 @@ impl Cancellable {
   }
 }
 
+/// Counts the ticks that `delay` spans, rounding up so that a delay shorter than a tick still waits one tick.
+fn ticks_of(delay: Duration, tick_duration: Duration) -> u128 {
+  delay.as_nanos().div_ceil(tick_duration.as_nanos())
+}
+
+/// Unlike `tokio::time::interval`, the first tick completes after `tick_duration` rather than immediately.
+fn tick_interval(tick_duration: Duration) -> Interval {
+  tokio::time::interval_at(Instant::now() + tick_duration, tick_duration)
+}
+
 #[derive(Debug, Clone)]
 pub struct Scheduler {
   tick_duration: Duration,
@@ -74,7 +85,7 @@ impl Scheduler {
 
   pub fn schedule_with_fixed_delay_to_actor_ref<U: Message>(
     &self,
-    runtime: Arc<Runtime>,
+    runtime: Handle,
     initial_delay: Duration,
     delay: Duration,
     receiver: ActorRef<U>,
@@ -87,7 +98,7 @@ impl Scheduler {
 
   pub fn schedule_with_fixed_delay<F>(
     &self,
-    runtime: Arc<Runtime>,
+    runtime: Handle,
     initial_delay: Duration,
     delay: Duration,
     f: F,
@@ -99,8 +110,8 @@ impl Scheduler {
     let tick_duration = self.tick_duration.clone();
     let join_handle = runtime.spawn(async move {
       log::debug!("Task started!");
-      let mut interval = tokio::time::interval(tick_duration);
-      let total_intervals = initial_delay.as_millis() / tick_duration.as_millis();
+      let mut interval = tick_interval(tick_duration);
+      let total_intervals = ticks_of(initial_delay, tick_duration);
       log::debug!("interval: {}", total_intervals);
       log::debug!("total_intervals: {}", total_intervals);
 
@@ -115,8 +126,8 @@ impl Scheduler {
 
       f();
 
-      let mut interval = tokio::time::interval(tick_duration);
-      let total_intervals = delay.as_millis() / tick_duration.as_millis();
+      let mut interval = tick_interval(tick_duration);
+      let total_intervals = ticks_of(delay, tick_duration);
       log::debug!("interval: {}", total_intervals);
       log::debug!("total_intervals: {}", total_intervals);
 
@@ -141,7 +152,7 @@ impl Scheduler {
 
   pub fn schedule_once_to_actor_ref<U: Message>(
     &self,
-    runtime: Arc<Runtime>,
+    runtime: Handle,
     delay: Duration,
     receiver: ActorRef<U>,
     message: U,
@@ -152,7 +163,7 @@ impl Scheduler {
     })
   }
 
-  pub fn schedule_once<F>(&self, runtime: Arc<Runtime>, delay: Duration, f: F) -> Cancellable
+  pub fn schedule_once<F>(&self, runtime: Handle, delay: Duration, f: F) -> Cancellable
   where
     F: Fn() + Send + 'static, {
     let cancellable = Cancellable::new();
@@ -160,8 +171,8 @@ impl Scheduler {
     let tick_duration = self.tick_duration.clone();
     let join_handle = runtime.spawn(async move {
       log::debug!("Task started!");
-      let mut interval = tokio::time::interval(tick_duration);
-      let total_intervals = delay.as_millis() / tick_duration.as_millis();
+      let mut interval = tick_interval(tick_duration);
+      let total_intervals = ticks_of(delay, tick_duration);
       log::debug!("interval: {}", total_intervals);
       log::debug!("total_intervals: {}", total_intervals);
 
@@ -192,10 +203,10 @@ mod tests {
   fn test_schedule_with_fixed_delay() {
     let delay = Duration::from_millis(1000);
     let _start = tokio::time::Instant::now();
-    let runtime = Arc::new(Runtime::new().unwrap());
+    let runtime = runtime::Runtime::new().unwrap();
 
     let cancellable = Scheduler::new(Duration::from_millis(10)).schedule_with_fixed_delay(
-      runtime.clone(),
+      runtime.handle().clone(),
       delay.clone(),
       delay,
       move || {
@@ -227,9 +238,9 @@ mod tests {
   fn test_schedule_once() {
     let delay = Duration::from_millis(1000);
     let start = tokio::time::Instant::now();
-    let runtime = Arc::new(Runtime::new().unwrap());
+    let runtime = runtime::Runtime::new().unwrap();
 
-    let cancellable = Scheduler::new(Duration::from_millis(10)).schedule_once(runtime.clone(), delay, || {
+    let cancellable = Scheduler::new(Duration::from_millis(10)).schedule_once(runtime.handle().clone(), delay, || {
       log::debug!(">>> Task executed!");
     });
 
@@ -251,6 +262,20 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_schedule_once_rounds_a_delay_up_to_the_next_tick() {
+    let tick_duration = Duration::from_millis(200);
+    let start = tokio::time::Instant::now();
+    let runtime = runtime::Runtime::new().unwrap();
+
+    let cancellable =
+      Scheduler::new(tick_duration).schedule_once(runtime.handle().clone(), Duration::from_millis(1), || {});
+    assert!(cancellable.join().is_ok());
+
+    let actual = start.elapsed();
+    assert!(actual >= tick_duration, "actual = {:?}, expected at least {:?}", actual, tick_duration);
+  }
+
   // #[test]
   // fn test_schedule_once_no_handle() {
   //   let mut cancellable = Cancellable::new();
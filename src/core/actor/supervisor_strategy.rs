@@ -0,0 +1,329 @@
+use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior};
+use crate::core::actor::children_refs::ChildrenRefs;
+use crate::core::actor::ActorError;
+use crate::core::dispatch::any_message::AnyMessage;
+use crate::core::dispatch::system_message::system_message::SystemMessage;
+use crate::core::dispatch::system_message::system_message_entry::SystemMessageEntry;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Directive {
+  Resume,
+  Restart,
+  Stop,
+  Escalate,
+}
+
+pub type Decider = Rc<dyn Fn(&ActorError) -> Directive>;
+
+pub fn default_decider() -> Decider {
+  Rc::new(|error| match error {
+    ActorError::ActorKilled => Directive::Stop,
+    _ => Directive::Restart,
+  })
+}
+
+#[derive(Clone)]
+pub enum SupervisorStrategy {
+  OneForOne {
+    max_nr_of_retries: Option<i32>,
+    within_time_range: Option<Duration>,
+    decider: Decider,
+  },
+  AllForOne {
+    max_nr_of_retries: Option<i32>,
+    within_time_range: Option<Duration>,
+    decider: Decider,
+  },
+}
+
+impl Debug for SupervisorStrategy {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    let (name, max_nr_of_retries, within_time_range) = match self {
+      SupervisorStrategy::OneForOne {
+        max_nr_of_retries,
+        within_time_range,
+        ..
+      } => ("OneForOne", max_nr_of_retries, within_time_range),
+      SupervisorStrategy::AllForOne {
+        max_nr_of_retries,
+        within_time_range,
+        ..
+      } => ("AllForOne", max_nr_of_retries, within_time_range),
+    };
+    f.debug_struct(name)
+      .field("max_nr_of_retries", max_nr_of_retries)
+      .field("within_time_range", within_time_range)
+      .field("decider", &"Fn")
+      .finish()
+  }
+}
+
+impl Default for SupervisorStrategy {
+  fn default() -> Self {
+    SupervisorStrategy::OneForOne {
+      max_nr_of_retries: None,
+      within_time_range: None,
+      decider: default_decider(),
+    }
+  }
+}
+
+impl SupervisorStrategy {
+  pub fn of_one_for_one<F>(max_nr_of_retries: Option<i32>, within_time_range: Option<Duration>, decider: F) -> Self
+  where
+    F: Fn(&ActorError) -> Directive + 'static, {
+    SupervisorStrategy::OneForOne {
+      max_nr_of_retries,
+      within_time_range,
+      decider: Rc::new(decider),
+    }
+  }
+
+  pub fn of_all_for_one<F>(max_nr_of_retries: Option<i32>, within_time_range: Option<Duration>, decider: F) -> Self
+  where
+    F: Fn(&ActorError) -> Directive + 'static, {
+    SupervisorStrategy::AllForOne {
+      max_nr_of_retries,
+      within_time_range,
+      decider: Rc::new(decider),
+    }
+  }
+
+  pub fn decide(&self, error: &ActorError) -> Directive {
+    match self {
+      SupervisorStrategy::OneForOne { decider, .. } => decider(error),
+      SupervisorStrategy::AllForOne { decider, .. } => decider(error),
+    }
+  }
+
+  /// Returns the maximum number of retries and the time range in milliseconds, saturated at `i32::MAX`.
+  pub fn retries_window(&self) -> (Option<i32>, Option<i32>) {
+    let (max_nr_of_retries, within_time_range) = match self {
+      SupervisorStrategy::OneForOne {
+        max_nr_of_retries,
+        within_time_range,
+        ..
+      } => (max_nr_of_retries, within_time_range),
+      SupervisorStrategy::AllForOne {
+        max_nr_of_retries,
+        within_time_range,
+        ..
+      } => (max_nr_of_retries, within_time_range),
+    };
+    (
+      *max_nr_of_retries,
+      within_time_range.map(|d| i32::try_from(d.as_millis()).unwrap_or(i32::MAX)),
+    )
+  }
+
+  /// Applies the directive decided for `cause` to the failed child.
+  ///
+  /// Returns `false` when the failure has to be escalated to the supervisor's own parent.
+  pub fn handle_failure(&self, children: &ChildrenRefs, child: ActorRef<AnyMessage>, cause: ActorError) -> bool {
+    let directive = self.decide(&cause);
+    log::debug!(
+      "handle_failure: child = {}, cause = {:?}, directive = {:?}",
+      child.path(),
+      cause,
+      directive
+    );
+    match directive {
+      Directive::Resume => {
+        Self::resume_child(child, cause);
+        true
+      }
+      Directive::Restart => {
+        self.process_failure(true, children, child, cause);
+        true
+      }
+      Directive::Stop => {
+        self.process_failure(false, children, child, cause);
+        true
+      }
+      Directive::Escalate => false,
+    }
+  }
+
+  fn process_failure(&self, restart: bool, children: &ChildrenRefs, child: ActorRef<AnyMessage>, cause: ActorError) {
+    let retries_window = self.retries_window();
+    match self {
+      SupervisorStrategy::OneForOne { .. } => {
+        if restart && children.request_restart_permission(&child, retries_window) {
+          Self::restart_child(child, cause, false);
+        } else {
+          Self::stop_child(child);
+        }
+      }
+      SupervisorStrategy::AllForOne { .. } => {
+        let all_children = children.children();
+        if all_children.is_empty() {
+          return;
+        }
+        // Every child is asked, so that the retry counters of all of them stay in step.
+        let permitted = restart
+          && all_children
+            .iter()
+            .map(|c| children.request_restart_permission(c, retries_window))
+            .fold(true, |a, b| a & b);
+        if permitted {
+          for c in all_children {
+            let suspend_first = c != child;
+            Self::restart_child(c, cause.clone(), suspend_first);
+          }
+        } else {
+          for c in all_children {
+            Self::stop_child(c);
+          }
+        }
+      }
+    }
+  }
+
+  fn resume_child(mut child: ActorRef<AnyMessage>, cause: ActorError) {
+    child.send_system_message(&mut SystemMessageEntry::new(SystemMessage::of_resume_with_failure(
+      Some(cause),
+    )));
+  }
+
+  fn restart_child(mut child: ActorRef<AnyMessage>, cause: ActorError, suspend_first: bool) {
+    if suspend_first {
+      child.send_system_message(&mut SystemMessageEntry::new(SystemMessage::of_suspend()));
+    }
+    child.send_system_message(&mut SystemMessageEntry::new(SystemMessage::of_recreate(cause)));
+  }
+
+  fn stop_child(mut child: ActorRef<AnyMessage>) {
+    log::debug!("Stopping child: {}", child.path());
+    child.stop();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::core::actor::actor_path::ActorPath;
+  use crate::core::actor::child_state::ChildRestartStats;
+  use crate::core::actor::props::FunctionProps;
+  use crate::core::actor::test_kit::{start_system, FailingChildActor, SupervisingActor, EVENT_TIMEOUT};
+  use std::sync::mpsc::channel;
+
+  #[test]
+  fn test_default_decider_restarts() {
+    let strategy = SupervisorStrategy::default();
+    let error = ActorError::ActorFailed {
+      message: "boom".to_string(),
+    };
+    assert_eq!(strategy.decide(&error), Directive::Restart);
+    assert_eq!(strategy.retries_window(), (None, None));
+  }
+
+  #[test]
+  fn test_default_decider_stops_killed() {
+    let strategy = SupervisorStrategy::default();
+    assert_eq!(strategy.decide(&ActorError::ActorKilled), Directive::Stop);
+  }
+
+  #[test]
+  fn test_custom_decider() {
+    let strategy = SupervisorStrategy::of_all_for_one(Some(3), Some(Duration::from_secs(1)), |e| match e {
+      ActorError::ActorFailed { message } if message == "resume" => Directive::Resume,
+      _ => Directive::Escalate,
+    });
+    let resume = ActorError::ActorFailed {
+      message: "resume".to_string(),
+    };
+    let other = ActorError::ActorFailed {
+      message: "other".to_string(),
+    };
+    assert_eq!(strategy.decide(&resume), Directive::Resume);
+    assert_eq!(strategy.decide(&other), Directive::Escalate);
+    assert_eq!(strategy.retries_window(), (Some(3), Some(1000)));
+  }
+
+  #[test]
+  fn test_escalate_returns_false() {
+    let strategy = SupervisorStrategy::of_one_for_one(None, None, |_| Directive::Escalate);
+    let children = ChildrenRefs::new();
+    let child = ActorRef::of_mock(ActorPath::from_string("test://test/child"));
+    let error = ActorError::ActorFailed {
+      message: "boom".to_string(),
+    };
+    assert!(!strategy.handle_failure(&children, child, error));
+  }
+
+  #[test]
+  fn test_large_time_range_saturates_instead_of_wrapping() {
+    let strategy = SupervisorStrategy::of_one_for_one(Some(1), Some(Duration::from_secs(30 * 24 * 60 * 60)), |_| {
+      Directive::Restart
+    });
+    assert_eq!(strategy.retries_window(), (Some(1), Some(i32::MAX)));
+
+    let child = ActorRef::of_mock(ActorPath::from_string("test://test/child"));
+    let mut stats = ChildRestartStats::new(child);
+    assert!(stats.request_restart_permission(strategy.retries_window()));
+    assert!(!stats.request_restart_permission(strategy.retries_window()));
+  }
+
+  #[test]
+  fn test_restart_permission_without_window() {
+    let child = ActorRef::of_mock(ActorPath::from_string("test://test/child"));
+    let mut stats = ChildRestartStats::new(child);
+    assert!(!stats.request_restart_permission((Some(0), None)));
+    assert!(stats.request_restart_permission((Some(1), None)));
+    assert!(!stats.request_restart_permission((Some(1), None)));
+    assert!(stats.request_restart_permission((None, None)));
+  }
+
+  #[test]
+  fn test_all_for_one_counts_retries_of_every_child_only_on_restart() {
+    let strategy = SupervisorStrategy::of_all_for_one(Some(1), None, |e| match e {
+      ActorError::ActorFailed { message } if message == "stop" => Directive::Stop,
+      _ => Directive::Restart,
+    });
+    let mut children = ChildrenRefs::new();
+    let child_a = ActorRef::of_mock(ActorPath::from_string("test://test/a"));
+    let child_b = ActorRef::of_mock(ActorPath::from_string("test://test/b"));
+    for (child, name) in [(&child_a, "a"), (&child_b, "b")] {
+      children.reserve_child(name);
+      children.init_child(child.clone(), name);
+    }
+    let stats_of = |name: &str| {
+      children
+        .get_child_state_by_name(name)
+        .and_then(|state| state.as_child_restart_stats().cloned())
+        .unwrap()
+    };
+    let failure = |message: &str| ActorError::ActorFailed {
+      message: message.to_string(),
+    };
+
+    assert!(strategy.handle_failure(&children, child_a.clone(), failure("stop")));
+    assert_eq!(stats_of("a"), ChildRestartStats::new(child_a.clone()));
+    assert_eq!(stats_of("b"), ChildRestartStats::new(child_b.clone()));
+
+    assert!(strategy.handle_failure(&children, child_a.clone(), failure("boom")));
+    assert!(strategy.handle_failure(&children, child_a.clone(), failure("boom")));
+    assert_eq!(stats_of("a"), ChildRestartStats::new_with(child_a, 2, None));
+    assert_eq!(stats_of("b"), ChildRestartStats::new_with(child_b, 2, None));
+  }
+
+  #[test]
+  fn test_one_for_one_stops_failed_child() {
+    let (terminated_tx, terminated_rx) = channel();
+    let main_props = FunctionProps::of_actor_with_args(terminated_tx, |terminated_tx| {
+      SupervisingActor::new(
+        Rc::new(FunctionProps::of_actor(|| FailingChildActor)),
+        SupervisorStrategy::of_one_for_one(None, None, |_| Directive::Stop),
+        terminated_tx.clone(),
+      )
+    });
+    let (_actor_system, mut supervising_ref) = start_system(Rc::new(main_props));
+
+    supervising_ref.tell("boom".to_string());
+    assert_eq!(terminated_rx.recv_timeout(EVENT_TIMEOUT), Ok("failing-child".to_string()));
+  }
+}
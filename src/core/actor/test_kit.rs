@@ -0,0 +1,315 @@
+use crate::core::actor::actor_context::{ActorContext, ActorContextBehavior};
+use crate::core::actor::actor_path::ActorPathBehavior;
+use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior};
+use crate::core::actor::actor_system::actor_system_builder::ActorSystemBuilder;
+use crate::core::actor::actor_system::ActorSystem;
+use crate::core::actor::props::{FunctionProps, Props};
+use crate::core::actor::supervisor_strategy::SupervisorStrategy;
+use crate::core::actor::{ActorBehavior, ActorError, ActorResult};
+use crate::core::dispatch::any_message::AnyMessage;
+use crate::core::dispatch::mailbox::dead_letter::DeadLetter;
+use crate::core::dispatch::message::Message;
+use std::env;
+use std::future::Future;
+use std::rc::Rc;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::time::Duration;
+use tokio::runtime;
+
+/// How long a probe waits for an event before the test fails.
+pub(crate) const EVENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+pub(crate) fn init_logger() {
+  env::set_var("RUST_LOG", "debug");
+  let _ = env_logger::builder().is_test(true).try_init();
+}
+
+/// Starts an actor system named `test` and returns it with the actor created from `main_props`.
+pub(crate) fn start_system<Msg: Message>(main_props: Rc<dyn Props<Msg>>) -> (ActorSystem<Msg>, ActorRef<Msg>) {
+  init_logger();
+  let actor_system = ActorSystemBuilder::new("test")
+    .with_guardian_props(main_props)
+    .build()
+    .unwrap();
+  let main_ref = actor_system.guardian_ref();
+  (actor_system, main_ref)
+}
+
+/// Blocks the test thread until `future` resolves.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+  let runner = runtime::Builder::new_current_thread().enable_all().build().unwrap();
+  runner.block_on(future)
+}
+
+/// Collects the events that test actors report through the senders it hands out.
+#[derive(Debug)]
+pub(crate) struct EventProbe {
+  events_tx: Sender<String>,
+  events_rx: Receiver<String>,
+}
+
+impl EventProbe {
+  pub(crate) fn new() -> Self {
+    let (events_tx, events_rx) = channel();
+    Self { events_tx, events_rx }
+  }
+
+  pub(crate) fn events_tx(&self) -> Sender<String> {
+    self.events_tx.clone()
+  }
+
+  pub(crate) fn next(&self) -> String {
+    self
+      .events_rx
+      .recv_timeout(EVENT_TIMEOUT)
+      .expect("EventProbe: no event within the timeout")
+  }
+
+  pub(crate) fn next_n(&self, n: usize) -> Vec<String> {
+    (0..n).map(|_| self.next()).collect()
+  }
+
+  /// Like `next_n`, for events whose order is not deterministic.
+  pub(crate) fn next_n_sorted(&self, n: usize) -> Vec<String> {
+    let mut events = self.next_n(n);
+    events.sort();
+    events
+  }
+
+  /// Returns the next `n` events that satisfy `predicate`, skipping the others.
+  pub(crate) fn next_n_matching<P>(&self, n: usize, predicate: P) -> Vec<String>
+  where
+    P: Fn(&str) -> bool, {
+    std::iter::repeat_with(|| self.next())
+      .filter(|event| predicate(event))
+      .take(n)
+      .collect()
+  }
+
+  /// Skips events until `expected` arrives.
+  pub(crate) fn wait_for(&self, expected: &str) {
+    while self.next() != expected {}
+  }
+
+  /// Returns the events received so far without waiting.
+  pub(crate) fn drain(&self) -> Vec<String> {
+    self.events_rx.try_iter().collect()
+  }
+
+  pub(crate) fn expect_no_event(&self, within: Duration) {
+    if let Ok(event) = self.events_rx.recv_timeout(within) {
+      panic!("EventProbe: unexpected event {}", event);
+    }
+  }
+}
+
+/// Reports every message it receives, and its own stop, as `<name>:<message>`.
+#[derive(Debug, Clone)]
+pub(crate) struct ReportingActor {
+  events_tx: Sender<String>,
+}
+
+impl ReportingActor {
+  pub(crate) fn new(events_tx: Sender<String>) -> Self {
+    Self { events_tx }
+  }
+
+  pub(crate) fn props(events_tx: Sender<String>) -> Rc<dyn Props<String>> {
+    Rc::new(FunctionProps::of_actor(move || ReportingActor::new(events_tx.clone())))
+  }
+
+  fn report<Msg: Message>(&self, ctx: &ActorContext<Msg>, event: &str) {
+    let name = ctx.self_ref().path().name().to_string();
+    let _ = self.events_tx.send(format!("{}:{}", name, event));
+  }
+}
+
+impl ActorBehavior<String> for ReportingActor {
+  fn receive(&mut self, ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+    self.report(&ctx, &msg);
+    Ok(())
+  }
+
+  fn post_stop(&mut self, ctx: ActorContext<String>) -> ActorResult<()> {
+    self.report(&ctx, "post_stop");
+    Ok(())
+  }
+}
+
+/// Spawns a child from `child_props`, named by each message it receives, and replies `spawned:<name>`.
+#[derive(Debug, Clone)]
+pub(crate) struct SpawningActor {
+  child_props: Rc<dyn Props<String>>,
+}
+
+impl SpawningActor {
+  pub(crate) fn props(child_props: Rc<dyn Props<String>>) -> Rc<dyn Props<String>> {
+    Rc::new(FunctionProps::of_actor(move || SpawningActor {
+      child_props: child_props.clone(),
+    }))
+  }
+}
+
+impl ActorBehavior<String> for SpawningActor {
+  fn receive(&mut self, mut ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+    ctx.spawn(self.child_props.clone(), &msg);
+    ctx.reply(format!("spawned:{}", msg));
+    Ok(())
+  }
+}
+
+/// Reports every dead letter as `<message>:<sender name>-><recipient name>`, with `noSender` for a missing sender.
+#[derive(Debug, Clone)]
+pub(crate) struct DeadLetterListener {
+  events_tx: Sender<String>,
+}
+
+impl DeadLetterListener {
+  pub(crate) fn props(events_tx: Sender<String>) -> Rc<dyn Props<DeadLetter<AnyMessage>>> {
+    Rc::new(FunctionProps::of_actor(move || DeadLetterListener {
+      events_tx: events_tx.clone(),
+    }))
+  }
+}
+
+impl ActorBehavior<DeadLetter<AnyMessage>> for DeadLetterListener {
+  fn receive(&mut self, _ctx: ActorContext<DeadLetter<AnyMessage>>, msg: DeadLetter<AnyMessage>) -> ActorResult<()> {
+    let message = msg.message().take::<String>().unwrap();
+    let sender = match msg.sender() {
+      ActorRef::NoSender => "noSender".to_string(),
+      sender => sender.path().name().to_string(),
+    };
+    let recipient = msg.recipient().path().name().to_string();
+    let _ = self.events_tx.send(format!("{}:{}->{}", message, sender, recipient));
+    Ok(())
+  }
+}
+
+/// Fails on every message it receives.
+#[derive(Debug, Clone)]
+pub(crate) struct FailingChildActor;
+
+impl ActorBehavior<String> for FailingChildActor {
+  fn receive(&mut self, _ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+    Err(ActorError::ActorFailed { message: msg })
+  }
+}
+
+/// Spawns `failing-child` from `child_props`, forwards every message to it and supervises it with `strategy`.
+///
+/// Reports the name of every child that terminates to `terminated_tx`.
+#[derive(Debug, Clone)]
+pub(crate) struct SupervisingActor {
+  child_props: Rc<dyn Props<String>>,
+  strategy: SupervisorStrategy,
+  child_ref: Option<ActorRef<String>>,
+  terminated_tx: Sender<String>,
+}
+
+impl SupervisingActor {
+  pub(crate) fn new(
+    child_props: Rc<dyn Props<String>>,
+    strategy: SupervisorStrategy,
+    terminated_tx: Sender<String>,
+  ) -> Self {
+    Self {
+      child_props,
+      strategy,
+      child_ref: None,
+      terminated_tx,
+    }
+  }
+}
+
+impl ActorBehavior<String> for SupervisingActor {
+  fn pre_start(&mut self, mut ctx: ActorContext<String>) -> ActorResult<()> {
+    self.child_ref = Some(ctx.spawn(self.child_props.clone(), "failing-child"));
+    Ok(())
+  }
+
+  fn receive(&mut self, _ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+    self.child_ref.as_mut().unwrap().tell(msg);
+    Ok(())
+  }
+
+  fn child_terminated(&mut self, child: ActorRef<AnyMessage>) -> ActorResult<()> {
+    self.terminated_tx.send(child.path().name().to_string()).unwrap();
+    Ok(())
+  }
+
+  fn supervisor_strategy(&self) -> SupervisorStrategy {
+    self.strategy.clone()
+  }
+}
+
+/// Reports its lifecycle and the messages it receives as `<name>:<event>`.
+///
+/// Messages prefixed with `child:` go to the child created from `child_props` instead; `child:kill` kills it.
+#[derive(Debug, Clone)]
+pub(crate) struct EventActor {
+  name: String,
+  events_tx: Sender<String>,
+  child_props: Option<Rc<dyn Props<String>>>,
+  child_ref: Option<ActorRef<String>>,
+}
+
+impl EventActor {
+  pub(crate) fn new(name: &str, events_tx: Sender<String>, child_props: Option<Rc<dyn Props<String>>>) -> Self {
+    Self {
+      name: name.to_string(),
+      events_tx,
+      child_props,
+      child_ref: None,
+    }
+  }
+
+  /// Returns props of an `EventActor` named `name`, with a child created from `child_props` if given.
+  pub(crate) fn props(
+    name: &str,
+    events_tx: Sender<String>,
+    child_props: Option<Rc<dyn Props<String>>>,
+  ) -> Rc<dyn Props<String>> {
+    let name = name.to_string();
+    Rc::new(FunctionProps::of_actor(move || {
+      EventActor::new(&name, events_tx.clone(), child_props.clone())
+    }))
+  }
+
+  fn emit(&self, event: &str) {
+    let _ = self.events_tx.send(format!("{}:{}", self.name, event));
+  }
+}
+
+impl ActorBehavior<String> for EventActor {
+  fn receive(&mut self, _ctx: ActorContext<String>, msg: String) -> ActorResult<()> {
+    match (msg.strip_prefix("child:"), self.child_ref.as_mut()) {
+      (Some("kill"), Some(child_ref)) => child_ref.kill(),
+      (Some(child_msg), Some(child_ref)) => child_ref.tell(child_msg.to_string()),
+      _ => self.emit(&format!("received:{}", msg)),
+    }
+    Ok(())
+  }
+
+  fn pre_start(&mut self, mut ctx: ActorContext<String>) -> ActorResult<()> {
+    if let Some(child_props) = self.child_props.clone() {
+      self.child_ref = Some(ctx.spawn(child_props, "child"));
+    }
+    self.emit("pre_start");
+    Ok(())
+  }
+
+  fn pre_suspend(&mut self, _ctx: ActorContext<String>) -> ActorResult<()> {
+    self.emit("pre_suspend");
+    Ok(())
+  }
+
+  fn post_resume(&mut self, _ctx: ActorContext<String>, _caused_by_failure: Option<ActorError>) -> ActorResult<()> {
+    self.emit("post_resume");
+    Ok(())
+  }
+
+  fn post_stop(&mut self, _ctx: ActorContext<String>) -> ActorResult<()> {
+    self.emit("post_stop");
+    Ok(())
+  }
+}
@@ -1,5 +1,6 @@
 use crate::core::actor::actor_cell::ActorCellBehavior;
 use crate::core::actor::actor_ref::ActorRef;
+use crate::core::actor::scheduler::Scheduler;
 use crate::core::dispatch::envelope::Envelope;
 use crate::core::dispatch::mailbox::mailbox::Mailbox;
 use crate::core::dispatch::mailbox::mailbox_type::{MailboxType, MailboxTypeBehavior};
@@ -8,17 +9,27 @@ use crate::core::dispatch::message::Message;
 use crate::core::dispatch::mailbox::{MailboxReaderBehavior, MailboxWriterBehavior};
 
 use crate::core::actor::actor_cell_with_ref::ActorCellWithRef;
+use crate::core::actor::actor_ref_provider::temp_container::TempContainer;
+use crate::core::actor::actor_system::actor_system_settings::DispatcherSettings;
+use crate::core::actor::deployer::Deployer;
 use crate::core::dispatch::mailboxes::Mailboxes;
 use crate::core::dispatch::system_message::system_message_entry::SystemMessageEntry;
 use crate::core::dispatch::system_message::SystemMessageQueueWriterBehavior;
 use std::sync::{Arc, Mutex};
-use tokio::runtime::Runtime;
+use std::time::Duration;
+use tokio::runtime::Handle;
 use tokio::task::JoinHandle;
 
 #[derive(Debug, Clone)]
 pub struct Dispatcher {
-  runtime: Arc<Runtime>,
+  runtime: Handle,
   mailboxes: Arc<Mutex<Mailboxes>>,
+  scheduler: Scheduler,
+  throughput: usize,
+  throughput_deadline: Option<Duration>,
+  deployer: Deployer,
+  temp_container: TempContainer,
+  dispatchers: Arc<Vec<DispatcherSettings>>,
   tasks: Arc<Mutex<Vec<JoinHandle<()>>>>,
 }
 
@@ -31,25 +42,108 @@ pub trait DispatcherBehavior {
   fn attach<U: Message>(&mut self, actor_cell: ActorCellWithRef<U>);
   fn detach<U: Message>(&mut self, actor_cell: ActorCellWithRef<U>);
 
+  fn suspend<U: Message>(&mut self, actor_cell: ActorCellWithRef<U>);
+  fn resume<U: Message>(&mut self, actor_cell: ActorCellWithRef<U>);
+
   fn dispatch<U: Message>(&mut self, actor_cell: ActorCellWithRef<U>, message: Envelope);
   fn system_dispatch<U: Message>(&mut self, actor_cell: ActorCellWithRef<U>, system_message: &mut SystemMessageEntry);
 }
 
 impl Dispatcher {
   const BLOCKING_MODE: bool = false;
+  pub const DEFAULT_THROUGHPUT: usize = 1;
+  pub const SCHEDULER_TICK_DURATION: Duration = Duration::from_millis(10);
+
+  pub fn new(runtime: Handle, mailboxes: Arc<Mutex<Mailboxes>>) -> Self {
+    Self::new_with_settings(
+      runtime,
+      mailboxes,
+      Self::DEFAULT_THROUGHPUT,
+      None,
+      Self::SCHEDULER_TICK_DURATION,
+    )
+  }
 
-  pub fn new(runtime: Arc<Runtime>, mailboxes: Arc<Mutex<Mailboxes>>) -> Self {
+  /// `throughput` is the number of messages an actor processes before its mailbox yields to other actors, and
+  /// `throughput_deadline` optionally bounds the time that takes.
+  pub fn new_with_settings(
+    runtime: Handle,
+    mailboxes: Arc<Mutex<Mailboxes>>,
+    throughput: usize,
+    throughput_deadline: Option<Duration>,
+    scheduler_tick_duration: Duration,
+  ) -> Self {
     Self {
       runtime,
       mailboxes,
+      scheduler: Scheduler::new(scheduler_tick_duration),
+      throughput,
+      throughput_deadline,
+      deployer: Deployer::default(),
+      temp_container: TempContainer::default(),
+      dispatchers: Arc::new(Vec::new()),
       tasks: Arc::new(Mutex::new(Vec::new())),
     }
   }
 
+  pub fn with_deployer(mut self, deployer: Deployer) -> Self {
+    self.deployer = deployer;
+    self
+  }
+
+  /// Shares `temp_container` with the actors of this dispatcher, so that their temporary references resolve.
+  pub fn with_temp_container(mut self, temp_container: TempContainer) -> Self {
+    self.temp_container = temp_container;
+    self
+  }
+
+  /// Registers the dispatchers that deployments can refer to by name.
+  pub fn with_dispatchers(mut self, dispatchers: Vec<DispatcherSettings>) -> Self {
+    self.dispatchers = Arc::new(dispatchers);
+    self
+  }
+
+  pub fn deployer(&self) -> &Deployer {
+    &self.deployer
+  }
+
+  pub fn temp_container(&self) -> &TempContainer {
+    &self.temp_container
+  }
+
+  /// Returns the dispatcher registered as `name`. It shares the runtime, mailboxes and scheduler of this one.
+  pub fn lookup(&self, name: &str) -> Option<Dispatcher> {
+    self
+      .dispatchers
+      .iter()
+      .find(|settings| settings.name == name)
+      .map(|settings| Self {
+        throughput: settings.throughput,
+        throughput_deadline: settings.throughput_deadline,
+        ..self.clone()
+      })
+  }
+
   pub fn mailboxes(&self) -> Arc<Mutex<Mailboxes>> {
     self.mailboxes.clone()
   }
 
+  pub fn runtime(&self) -> Handle {
+    self.runtime.clone()
+  }
+
+  pub fn throughput(&self) -> usize {
+    self.throughput
+  }
+
+  pub fn throughput_deadline(&self) -> Option<Duration> {
+    self.throughput_deadline
+  }
+
+  pub fn scheduler(&self) -> Scheduler {
+    self.scheduler.clone()
+  }
+
   fn register<U: Message>(&mut self, _actor_cell: ActorCellWithRef<U>) {}
 
   fn unregister<U: Message>(&mut self, _actor_cell: ActorCellWithRef<U>) {}
@@ -123,6 +217,8 @@ impl DispatcherBehavior for Dispatcher {
   fn create_mailbox<U: Message>(&self, self_ref: Option<ActorRef<U>>, mailbox_type: MailboxType) -> Mailbox<U> {
     let message_queue = mailbox_type.create_message_queue(self_ref);
     Mailbox::new_with_message_queue(mailbox_type, message_queue)
+      .with_throughput(self.throughput)
+      .with_throughput_deadline(self.throughput_deadline)
   }
 
   fn attach<U: Message>(&mut self, actor_cell: ActorCellWithRef<U>) {
@@ -131,10 +227,29 @@ impl DispatcherBehavior for Dispatcher {
   }
 
   fn detach<U: Message>(&mut self, actor_cell: ActorCellWithRef<U>) {
+    let mut mailbox = actor_cell.mailbox();
+    mailbox.become_closed();
     self.unregister(actor_cell);
   }
 
+  fn suspend<U: Message>(&mut self, actor_cell: ActorCellWithRef<U>) {
+    let mut mailbox = actor_cell.mailbox();
+    mailbox.suspend();
+  }
+
+  fn resume<U: Message>(&mut self, actor_cell: ActorCellWithRef<U>) {
+    let mut mailbox = actor_cell.mailbox();
+    if mailbox.resume() {
+      self.register_for_execution(actor_cell, false, false);
+    }
+  }
+
   fn dispatch<U: Message>(&mut self, receiver: ActorCellWithRef<U>, invocation: Envelope) {
+    if receiver.mailbox().is_closed() {
+      let mut dead_letter_mailbox = receiver.dead_letter_mailbox();
+      dead_letter_mailbox.enqueue(receiver.actor_ref.clone().to_any(false), invocation);
+      return;
+    }
     let mut mailbox_sender = receiver.actor_cell.mailbox_sender();
     mailbox_sender.enqueue(receiver.actor_ref.clone(), invocation).unwrap();
     self.register_for_execution(receiver, true, false);
@@ -6,11 +6,11 @@ use crate::core::dispatch::message::Message;
 use crate::core::dispatch::message_queue::MessageQueueSize;
 use anyhow::Result;
 
-pub(crate) mod dead_letter;
+pub mod dead_letter;
 pub(crate) mod dead_letter_mailbox;
 pub(crate) mod mailbox;
 pub(crate) mod mailbox_status;
-pub(crate) mod mailbox_type;
+pub mod mailbox_type;
 pub(crate) mod system_mailbox;
 
 pub trait MailboxBehavior<Msg: Message> {
@@ -29,3 +29,7 @@ pub trait MailboxReaderBehavior<Msg: Message>: MailboxBehavior<Msg> {
 pub trait MailboxWriterBehavior<Msg: Message>: MailboxBehavior<Msg> {
   fn enqueue(&mut self, receiver: ActorRef<Msg>, msg: Envelope) -> Result<()>;
 }
+
+pub trait DequeMailboxWriterBehavior<Msg: Message>: MailboxWriterBehavior<Msg> {
+  fn enqueue_first(&mut self, receiver: ActorRef<Msg>, msg: Envelope) -> Result<()>;
+}
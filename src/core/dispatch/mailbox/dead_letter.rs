@@ -20,6 +20,18 @@ impl<Msg: Message> DeadLetter<Msg> {
       recipient,
     }
   }
+
+  pub fn message(&self) -> &AnyMessage {
+    &self.message
+  }
+
+  pub fn sender(&self) -> &ActorRef<Msg> {
+    &self.sender
+  }
+
+  pub fn recipient(&self) -> &ActorRef<Msg> {
+    &self.recipient
+  }
 }
 
 impl<Msg: Message> PartialEq for DeadLetter<Msg> {
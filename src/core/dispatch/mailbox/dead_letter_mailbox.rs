@@ -1,11 +1,13 @@
 use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior};
 use crate::core::dispatch::any_message::AnyMessage;
+use crate::core::dispatch::envelope::Envelope;
 use crate::core::dispatch::mailbox::dead_letter::DeadLetter;
 use crate::core::dispatch::mailbox::mailbox::Mailbox;
 use crate::core::dispatch::mailbox::MailboxBehavior;
 use crate::core::dispatch::message_queue::MessageQueueSize;
 use crate::core::dispatch::system_message::earliest_first_system_message_list::EarliestFirstSystemMessageList;
 use crate::core::dispatch::system_message::latest_first_system_message_list::LatestFirstSystemMessageList;
+use crate::core::dispatch::system_message::system_message::SystemMessage;
 use crate::core::dispatch::system_message::system_message_entry::SystemMessageEntry;
 use crate::core::dispatch::system_message::{SystemMessageQueueReaderBehavior, SystemMessageQueueWriterBehavior, ENIL};
 
@@ -22,6 +24,19 @@ impl DeadLetterMailbox {
       underlying,
     }
   }
+
+  pub fn dead_letters(&self) -> ActorRef<AnyMessage> {
+    self.dead_letters.clone()
+  }
+}
+
+impl DeadLetterMailbox {
+  /// Hands a message that could not be delivered to `receiver` over to dead letters, keeping its sender.
+  pub fn enqueue(&mut self, receiver: ActorRef<AnyMessage>, envelope: Envelope) {
+    let sender = envelope.sender().unwrap_or(ActorRef::NoSender);
+    let dead_letter = DeadLetter::new(envelope.message, sender, receiver);
+    self.dead_letters.tell(AnyMessage::new(dead_letter))
+  }
 }
 
 impl MailboxBehavior<AnyMessage> for DeadLetterMailbox {
@@ -36,6 +51,12 @@ impl MailboxBehavior<AnyMessage> for DeadLetterMailbox {
 
 impl SystemMessageQueueWriterBehavior<AnyMessage> for DeadLetterMailbox {
   fn system_enqueue(&mut self, receiver: ActorRef<AnyMessage>, message: &mut SystemMessageEntry) {
+    // A watchee that can no longer be reached is reported to its watcher as terminated.
+    if let Some((watchee, watcher)) = message.message.as_watch() {
+      let notification = SystemMessage::of_death_watch_notification(watchee.clone(), true, false);
+      watcher.clone().send_system_message(&mut SystemMessageEntry::new(notification));
+      return;
+    }
     let dead_letter = DeadLetter::new(AnyMessage::new(message.clone()), receiver.clone(), receiver);
     let any_message = AnyMessage::new(dead_letter);
     self.dead_letters.tell(any_message)
@@ -51,3 +72,26 @@ impl SystemMessageQueueReaderBehavior for DeadLetterMailbox {
     ENIL.clone()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::core::actor::actor_path::ActorPathBehavior;
+  use crate::core::actor::test_kit::{start_system, DeadLetterListener, EventProbe};
+  use crate::core::dispatch::mailbox::mailbox_type::MailboxType;
+  use crate::core::dispatch::mailboxes::Mailboxes;
+
+  #[test]
+  fn test_dead_letters_record_sender() {
+    let probe = EventProbe::new();
+    let (_actor_system, listener_ref) = start_system(DeadLetterListener::props(probe.events_tx()));
+    let mailboxes = Mailboxes::new(MailboxType::of_unbounded(), listener_ref.clone().to_any(true));
+    let sender = ActorRef::of_mock(listener_ref.path().with_child("sender"));
+    let recipient = ActorRef::of_mock(listener_ref.path().with_child("recipient"));
+    mailboxes
+      .dead_letter_mailbox()
+      .enqueue(recipient, Envelope::new_with_sender("lost".to_string(), sender));
+
+    assert_eq!(probe.next(), "lost:sender->recipient");
+  }
+}
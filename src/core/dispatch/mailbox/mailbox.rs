@@ -4,11 +4,13 @@ use crate::core::dispatch::envelope::Envelope;
 use crate::core::dispatch::mailbox::mailbox_status::MailboxStatus;
 use crate::core::dispatch::mailbox::mailbox_type::MailboxType;
 use crate::core::dispatch::mailbox::system_mailbox::SystemMailbox;
-use crate::core::dispatch::mailbox::{MailboxBehavior, MailboxReaderBehavior, MailboxWriterBehavior};
+use crate::core::dispatch::mailbox::{
+  DequeMailboxWriterBehavior, MailboxBehavior, MailboxReaderBehavior, MailboxWriterBehavior,
+};
 use crate::core::dispatch::message::Message;
 use crate::core::dispatch::message_queue::{
-  MessageQueue, MessageQueueBehavior, MessageQueueReaderBehavior, MessageQueueReaderFactoryBehavior, MessageQueueSize,
-  MessageQueueWriterBehavior, MessageQueueWriterFactoryBehavior,
+  DequeMessageQueueWriterBehavior, MessageQueue, MessageQueueBehavior, MessageQueueReaderBehavior,
+  MessageQueueReaderFactoryBehavior, MessageQueueSize, MessageQueueWriterBehavior, MessageQueueWriterFactoryBehavior,
 };
 use crate::core::dispatch::system_message::earliest_first_system_message_list::EarliestFirstSystemMessageList;
 use crate::core::dispatch::system_message::latest_first_system_message_list::LatestFirstSystemMessageList;
@@ -27,7 +29,7 @@ use std::fmt::{Debug, Formatter};
 
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 #[derive(Debug)]
 pub struct Terminate {
@@ -134,6 +136,28 @@ impl<Msg: Message> Mailbox<Msg> {
     }
   }
 
+  pub fn with_throughput(self, throughput: usize) -> Self {
+    {
+      let mut inner = mutex_lock_with_log!(self.inner, "with_throughput");
+      inner.throughput = throughput;
+    }
+    self
+  }
+
+  /// Limits the time a mailbox run may take; it yields once the deadline passes even if throughput allows more.
+  pub fn with_throughput_deadline(self, throughput_deadline: Option<Duration>) -> Self {
+    {
+      let mut inner = mutex_lock_with_log!(self.inner, "with_throughput_deadline");
+      inner
+        .is_throughput_deadline_time_defined
+        .store(throughput_deadline.is_some(), Ordering::Relaxed);
+      if let Some(throughput_deadline) = throughput_deadline {
+        inner.throughput_deadline_time = throughput_deadline;
+      }
+    }
+    self
+  }
+
   pub fn to_any(self) -> Mailbox<AnyMessage> {
     let inner = mutex_lock_with_log!(self.inner, "to_any");
     Mailbox {
@@ -153,6 +177,16 @@ impl<Msg: Message> Mailbox<Msg> {
     }
   }
 
+  pub fn mailbox_type(&self) -> MailboxType {
+    let inner = mutex_lock_with_log!(self.inner, "mailbox_type");
+    inner.mailbox_type.clone()
+  }
+
+  pub fn throughput(&self) -> usize {
+    let inner = mutex_lock_with_log!(self.inner, "throughput");
+    inner.throughput
+  }
+
   pub fn sender(&self) -> MailboxSender<Msg> {
     MailboxSender {
       underlying: self.clone(),
@@ -362,7 +396,7 @@ impl<Msg: Message> Mailbox<Msg> {
           let inner = mutex_lock_with_log!(self.inner, "process_mailbox");
           inner.throughput_deadline_time
         };
-        now.elapsed().unwrap().as_nanos() + throughput_deadline_time.as_nanos()
+        now.duration_since(UNIX_EPOCH).unwrap().as_nanos() + throughput_deadline_time.as_nanos()
       } else {
         0
       };
@@ -395,7 +429,7 @@ impl<Msg: Message> Mailbox<Msg> {
           actor_cell.invoke(&next);
           self.process_system_mailbox(actor_cell.clone(), self.clone()).await;
           let now = SystemTime::now();
-          if is_throughput_deadline_time_defined && (now.elapsed().unwrap().as_nanos() as u128) >= deadline_ns {
+          if is_throughput_deadline_time_defined && now.duration_since(UNIX_EPOCH).unwrap().as_nanos() >= deadline_ns {
             break;
           }
         }
@@ -533,6 +567,16 @@ impl<Msg: Message> MailboxWriterBehavior<Msg> for MailboxSender<Msg> {
   }
 }
 
+impl<Msg: Message> DequeMailboxWriterBehavior<Msg> for MailboxSender<Msg> {
+  fn enqueue_first(&mut self, receiver: ActorRef<Msg>, msg: Envelope) -> Result<()> {
+    let mq = {
+      let inner = mutex_lock_with_log!(self.underlying.inner, "enqueue_first");
+      inner.message_queue.clone()
+    };
+    mq.writer().enqueue_first(receiver, msg)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -568,4 +612,34 @@ mod tests {
     let sm_actual = result.head.unwrap().lock().unwrap().message.clone();
     assert_eq!(sm_actual, sm);
   }
+
+  #[test]
+  fn test_enqueue_first() {
+    init_logger();
+    let mailbox_type = MailboxType::of_unbounded_deque();
+    let mq: MessageQueue<String> = mailbox_type.create_message_queue(None);
+
+    let mut m = Mailbox::new_with_message_queue(mailbox_type, mq);
+    let mut ms = m.sender();
+
+    ms.enqueue(ActorRef::NoSender, Envelope::new("b".to_string())).unwrap();
+    ms.enqueue_first(ActorRef::NoSender, Envelope::new("a".to_string())).unwrap();
+
+    let result = m.dequeue().unwrap();
+    assert_eq!(result.unwrap().typed_message::<String>().unwrap(), "a".to_string());
+    let result = m.dequeue().unwrap();
+    assert_eq!(result.unwrap().typed_message::<String>().unwrap(), "b".to_string());
+  }
+
+  #[test]
+  fn test_enqueue_first_unsupported() {
+    init_logger();
+    let mailbox_type = MailboxType::of_unbounded();
+    let mq: MessageQueue<String> = mailbox_type.create_message_queue(None);
+
+    let m = Mailbox::new_with_message_queue(mailbox_type, mq);
+    let mut ms = m.sender();
+
+    assert!(ms.enqueue_first(ActorRef::NoSender, Envelope::new("a".to_string())).is_err());
+  }
 }
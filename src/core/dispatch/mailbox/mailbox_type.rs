@@ -8,6 +8,7 @@ use std::time::Duration;
 pub enum MailboxType {
   Unbounded,
   Bounded { capacity: usize, push_time_out: Duration },
+  UnboundedDeque { stash_capacity: Option<usize> },
 }
 
 impl MailboxType {
@@ -21,6 +22,28 @@ impl MailboxType {
       push_time_out,
     }
   }
+
+  pub fn of_unbounded_deque() -> Self {
+    MailboxType::UnboundedDeque { stash_capacity: None }
+  }
+
+  pub fn of_unbounded_deque_with_stash_capacity(stash_capacity: usize) -> Self {
+    MailboxType::UnboundedDeque {
+      stash_capacity: Some(stash_capacity),
+    }
+  }
+
+  /// Whether messages can be put back in front of the mailbox, which `stash` relies on.
+  pub fn is_deque(&self) -> bool {
+    matches!(self, MailboxType::UnboundedDeque { .. })
+  }
+
+  pub fn stash_capacity(&self) -> Option<usize> {
+    match self {
+      MailboxType::UnboundedDeque { stash_capacity } => *stash_capacity,
+      _ => None,
+    }
+  }
 }
 
 pub trait MailboxTypeBehavior<Msg: Message> {
@@ -37,6 +60,7 @@ impl<Msg: Message> MailboxTypeBehavior<Msg> for MailboxType {
       MailboxType::Bounded { capacity, .. } => {
         MessageQueue::of_bounded_with_queue_type_with_num_elements(QueueType::MPSC, *capacity)
       }
+      MailboxType::UnboundedDeque { .. } => MessageQueue::of_unbounded_with_queue_type(QueueType::Vec),
     }
   }
 }
@@ -207,9 +207,14 @@ impl<Msg: Message> SystemMessageQueueWriterBehavior<Msg> for SystemMailboxSender
       let system_message_guard = head_arc.lock().unwrap();
       system_message_guard.is_no_message()
     }) {
-      // TODO: デッドレターに送る
-      log::warn!("DeadLetter: {:?}", message);
-      ()
+      // The receiver has terminated: its watchers are told so right away, anything else is a dead letter.
+      match message.message.as_watch() {
+        Some((watchee, watcher)) => {
+          let notification = SystemMessage::of_death_watch_notification(watchee.clone(), true, false);
+          watcher.clone().send_system_message(&mut SystemMessageEntry::new(notification));
+        }
+        None => log::warn!("DeadLetter: {:?}", message),
+      }
     } else {
       if !self.system_queue_put(&current_list.clone(), &current_list.prepend(message.clone())) {
         // putに失敗した場合、やり直すが、実際には発生しない
@@ -6,21 +6,28 @@ use crate::core::dispatch::mailbox::mailbox_type::{MailboxType, MailboxTypeBehav
 
 #[derive(Debug, Clone)]
 pub struct Mailboxes {
+  default_mailbox_type: MailboxType,
   dead_letters: ActorRef<AnyMessage>,
   dead_letter_mailbox: DeadLetterMailbox,
 }
 
 impl Mailboxes {
+  /// `mailbox_type` is used for the dead letter mailbox and for actors whose props do not specify one.
   pub fn new(mailbox_type: MailboxType, dead_letters: ActorRef<AnyMessage>) -> Self {
     let mq = mailbox_type.create_message_queue(None);
-    let mailbox = Mailbox::new_with_message_queue(mailbox_type, mq);
+    let mailbox = Mailbox::new_with_message_queue(mailbox_type.clone(), mq);
     let dead_letter_mailbox = DeadLetterMailbox::new(dead_letters.clone(), mailbox);
     Self {
+      default_mailbox_type: mailbox_type,
       dead_letters,
       dead_letter_mailbox,
     }
   }
 
+  pub fn default_mailbox_type(&self) -> MailboxType {
+    self.default_mailbox_type.clone()
+  }
+
   pub fn dead_letter_mailbox(&self) -> DeadLetterMailbox {
     self.dead_letter_mailbox.clone()
   }
@@ -12,7 +12,7 @@ use crate::core::dispatch::message_queue::dead_letters_message_queue::{
 use crate::core::dispatch::message_queue::unbounded_message_queue::{
   UnboundedMessageQueue, UnboundedMessageQueueReader, UnboundedMessageQueueWriter,
 };
-use crate::infrastructure::queue::{create_queue, Queue, QueueType};
+use crate::infrastructure::queue::{create_queue, Queue, QueueError, QueueType};
 use anyhow::Result;
 
 mod bounded_message_queue;
@@ -51,6 +51,10 @@ pub trait MessageQueueWriterBehavior<Msg: Message>: MessageQueueBehavior<Msg> {
   fn enqueue(&mut self, receiver: ActorRef<Msg>, handle: Envelope) -> Result<()>;
 }
 
+pub trait DequeMessageQueueWriterBehavior<Msg: Message>: MessageQueueWriterBehavior<Msg> {
+  fn enqueue_first(&mut self, receiver: ActorRef<Msg>, handle: Envelope) -> Result<()>;
+}
+
 pub trait MessageQueueReaderBehavior<Msg: Message>: MessageQueueBehavior<Msg> {
   fn dequeue(&mut self) -> Result<Option<Envelope>>;
 }
@@ -222,6 +226,20 @@ impl<Msg: Message> MessageQueueWriterBehavior<Msg> for MessageQueueWriter<Msg> {
   }
 }
 
+impl<Msg: Message> DequeMessageQueueWriterBehavior<Msg> for MessageQueueWriter<Msg> {
+  fn enqueue_first(&mut self, receiver: ActorRef<Msg>, handle: Envelope) -> Result<()> {
+    match self {
+      MessageQueueWriter::Unbounded(queue) => queue.enqueue_first(receiver, handle),
+      MessageQueueWriter::Bounded(_) => Err(anyhow::Error::new(QueueError::<Envelope>::UnsupportedOperationError(
+        "enqueue_first on a bounded message queue",
+      ))),
+      MessageQueueWriter::DeadLetter(_) => Err(anyhow::Error::new(
+        QueueError::<Envelope>::UnsupportedOperationError("enqueue_first on a dead letters message queue"),
+      )),
+    }
+  }
+}
+
 impl<Msg: Message> MessageQueueBehavior<Msg> for MessageQueueReader<Msg> {
   fn number_of_messages(&self) -> MessageQueueSize {
     match self {
@@ -1,6 +1,7 @@
 use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior};
 use crate::core::dispatch::any_message::AnyMessage;
 use crate::core::dispatch::envelope::Envelope;
+use crate::core::dispatch::mailbox::dead_letter::DeadLetter;
 use crate::core::dispatch::message_queue::{
   MessageQueueBehavior, MessageQueueRWFactoryBehavior, MessageQueueReaderBehavior, MessageQueueReaderFactoryBehavior,
   MessageQueueSize, MessageQueueWithRWFactoryBehavior, MessageQueueWriterBehavior, MessageQueueWriterFactoryBehavior,
@@ -83,8 +84,10 @@ impl MessageQueueBehavior<AnyMessage> for DeadLettersMessageQueueWriter {
 }
 
 impl MessageQueueWriterBehavior<AnyMessage> for DeadLettersMessageQueueWriter {
-  fn enqueue(&mut self, _receiver: ActorRef<AnyMessage>, handle: Envelope) -> anyhow::Result<()> {
-    self.underlying.dead_letter_ref.tell(handle.message);
+  fn enqueue(&mut self, receiver: ActorRef<AnyMessage>, handle: Envelope) -> anyhow::Result<()> {
+    let sender = handle.sender().unwrap_or(ActorRef::NoSender);
+    let dead_letter = DeadLetter::new(handle.message, sender, receiver);
+    self.underlying.dead_letter_ref.tell(AnyMessage::new(dead_letter));
     Ok(())
   }
 }
@@ -3,12 +3,13 @@ use crate::core::dispatch::any_message::AnyMessage;
 use crate::core::dispatch::envelope::Envelope;
 use crate::core::dispatch::message::Message;
 use crate::core::dispatch::message_queue::{
-  MessageQueueBehavior, MessageQueueRWFactoryBehavior, MessageQueueReaderBehavior, MessageQueueReaderFactoryBehavior,
-  MessageQueueSize, MessageQueueWithRWFactoryBehavior, MessageQueueWriterBehavior, MessageQueueWriterFactoryBehavior,
+  DequeMessageQueueWriterBehavior, MessageQueueBehavior, MessageQueueRWFactoryBehavior, MessageQueueReaderBehavior,
+  MessageQueueReaderFactoryBehavior, MessageQueueSize, MessageQueueWithRWFactoryBehavior, MessageQueueWriterBehavior,
+  MessageQueueWriterFactoryBehavior,
 };
 use crate::infrastructure::queue::{
-  create_queue, Queue, QueueBehavior, QueueReader, QueueReaderBehavior, QueueReaderFactoryBehavior, QueueSize,
-  QueueType, QueueWriter, QueueWriterBehavior, QueueWriterFactoryBehavior,
+  create_queue, DequeWriterBehavior, Queue, QueueBehavior, QueueReader, QueueReaderBehavior, QueueReaderFactoryBehavior,
+  QueueSize, QueueType, QueueWriter, QueueWriterBehavior, QueueWriterFactoryBehavior,
 };
 
 #[derive(Debug, Clone)]
@@ -140,6 +141,12 @@ impl<Msg: Message> MessageQueueWriterBehavior<Msg> for UnboundedMessageQueueWrit
   }
 }
 
+impl<Msg: Message> DequeMessageQueueWriterBehavior<Msg> for UnboundedMessageQueueWriter<Msg> {
+  fn enqueue_first(&mut self, _receiver: ActorRef<Msg>, handle: Envelope) -> anyhow::Result<()> {
+    self.writer.offer_first(handle)
+  }
+}
+
 // ---
 
 impl<Msg: Message> MessageQueueBehavior<Msg> for UnboundedMessageQueueReader<Msg> {
@@ -20,7 +20,14 @@ pub enum SystemMessage {
     child: ActorRef<AnyMessage>,
     a_sync: bool,
   },
-  Watch,
+  Watch {
+    watchee: ActorRef<AnyMessage>,
+    watcher: ActorRef<AnyMessage>,
+  },
+  Unwatch {
+    watchee: ActorRef<AnyMessage>,
+    watcher: ActorRef<AnyMessage>,
+  },
   NoMessage,
   Failed {
     child: ActorRef<AnyMessage>,
@@ -69,8 +76,12 @@ impl SystemMessage {
     SystemMessage::Supervise { child, a_sync }
   }
 
-  pub fn of_watch() -> Self {
-    SystemMessage::Watch
+  pub fn of_watch(watchee: ActorRef<AnyMessage>, watcher: ActorRef<AnyMessage>) -> Self {
+    SystemMessage::Watch { watchee, watcher }
+  }
+
+  pub fn of_unwatch(watchee: ActorRef<AnyMessage>, watcher: ActorRef<AnyMessage>) -> Self {
+    SystemMessage::Unwatch { watchee, watcher }
   }
 
   pub fn of_no_message() -> Self {
@@ -101,13 +112,22 @@ impl SystemMessage {
       SystemMessage::Resume { .. } => "Resume".to_string(),
       SystemMessage::Terminate => "Terminate".to_string(),
       SystemMessage::Supervise { .. } => "Supervise".to_string(),
-      SystemMessage::Watch => "Watch".to_string(),
+      SystemMessage::Watch { .. } => "Watch".to_string(),
+      SystemMessage::Unwatch { .. } => "Unwatch".to_string(),
       SystemMessage::NoMessage => "NoMessage".to_string(),
       SystemMessage::Failed { .. } => "Failed".to_string(),
       SystemMessage::DeathWatchNotification { .. } => "DeathWatchNotification".to_string(),
     }
   }
 
+  /// Returns the watchee and the watcher of a `Watch`.
+  pub(crate) fn as_watch(&self) -> Option<(&ActorRef<AnyMessage>, &ActorRef<AnyMessage>)> {
+    match self {
+      SystemMessage::Watch { watchee, watcher } => Some((watchee, watcher)),
+      _ => None,
+    }
+  }
+
   pub fn is_no_message(&self) -> bool {
     match self {
       SystemMessage::NoMessage { .. } => true,
@@ -0,0 +1,328 @@
+use crate::core::actor::actor_context::{ActorContext, ActorContextBehavior};
+use crate::core::actor::actor_ref::{ActorRef, ActorRefBehavior};
+use crate::core::actor::deployer::Deploy;
+use crate::core::actor::props::Props;
+use crate::core::actor::supervisor_strategy::SupervisorStrategy;
+use crate::core::actor::{ActorBehavior, ActorResult};
+use crate::core::dispatch::any_message::AnyMessage;
+use crate::core::dispatch::mailbox::mailbox_type::MailboxType;
+use crate::core::dispatch::message::Message;
+use rand::{thread_rng, Rng};
+use std::cell::RefCell;
+use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
+
+/// How a pool router creates its routees and picks the ones a message goes to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RouterConfig {
+  RoundRobinPool { nr_of_instances: usize },
+  RandomPool { nr_of_instances: usize },
+  BroadcastPool { nr_of_instances: usize },
+}
+
+impl RouterConfig {
+  pub fn of_round_robin_pool(nr_of_instances: usize) -> Self {
+    RouterConfig::RoundRobinPool { nr_of_instances }
+  }
+
+  pub fn of_random_pool(nr_of_instances: usize) -> Self {
+    RouterConfig::RandomPool { nr_of_instances }
+  }
+
+  pub fn of_broadcast_pool(nr_of_instances: usize) -> Self {
+    RouterConfig::BroadcastPool { nr_of_instances }
+  }
+
+  pub fn nr_of_instances(&self) -> usize {
+    match self {
+      RouterConfig::RoundRobinPool { nr_of_instances }
+      | RouterConfig::RandomPool { nr_of_instances }
+      | RouterConfig::BroadcastPool { nr_of_instances } => *nr_of_instances,
+    }
+  }
+}
+
+/// Creates a router that spawns its routees from `routee_props` and forwards every message to them, keeping the
+/// original sender.
+pub struct RouterProps<Msg: Message> {
+  router_config: RouterConfig,
+  routee_props: Rc<dyn Props<Msg>>,
+}
+
+impl<Msg: Message> Debug for RouterProps<Msg> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("RouterProps")
+      .field("router_config", &self.router_config)
+      .field("routee_props", &self.routee_props)
+      .finish()
+  }
+}
+
+impl<Msg: Message> RouterProps<Msg> {
+  /// The router configuration of `routee_props` itself, if any, is ignored for the routees.
+  pub fn new(router_config: RouterConfig, routee_props: Rc<dyn Props<Msg>>) -> Self {
+    Self::new_with_deploy(router_config, routee_props, None)
+  }
+
+  /// Like `new`, but the mailbox type and the dispatcher of `deploy`, the deployment of the router, override those
+  /// of `routee_props` as well.
+  pub(crate) fn new_with_deploy(
+    router_config: RouterConfig,
+    routee_props: Rc<dyn Props<Msg>>,
+    deploy: Option<Deploy>,
+  ) -> Self {
+    Self {
+      router_config,
+      routee_props: Rc::new(RouteeProps {
+        underlying: routee_props,
+        deploy,
+      }),
+    }
+  }
+}
+
+impl<Msg: Message> Props<Msg> for RouterProps<Msg> {
+  fn new_actor(&self) -> Rc<RefCell<dyn ActorBehavior<Msg>>> {
+    Rc::new(RefCell::new(RouterActor {
+      router_config: self.router_config.clone(),
+      routee_props: self.routee_props.clone(),
+      routees: Vec::new(),
+      next_index: 0,
+    }))
+  }
+}
+
+/// Keeps the settings of the props a router was created from and of its deployment, except the router
+/// configuration, so that the routees do not become routers themselves.
+#[derive(Debug)]
+struct RouteeProps<Msg: Message> {
+  underlying: Rc<dyn Props<Msg>>,
+  deploy: Option<Deploy>,
+}
+
+impl<Msg: Message> Props<Msg> for RouteeProps<Msg> {
+  fn new_actor(&self) -> Rc<RefCell<dyn ActorBehavior<Msg>>> {
+    self.underlying.new_actor()
+  }
+
+  fn mailbox_type(&self) -> Option<MailboxType> {
+    self
+      .deploy
+      .as_ref()
+      .and_then(|deploy| deploy.mailbox_type.clone())
+      .or_else(|| self.underlying.mailbox_type())
+  }
+
+  fn dispatcher(&self) -> Option<String> {
+    self
+      .deploy
+      .as_ref()
+      .and_then(|deploy| deploy.dispatcher.clone())
+      .or_else(|| self.underlying.dispatcher())
+  }
+
+  fn supervisor_strategy(&self) -> Option<SupervisorStrategy> {
+    self.underlying.supervisor_strategy()
+  }
+
+  fn is_routee(&self) -> bool {
+    true
+  }
+}
+
+#[derive(Debug)]
+struct RouterActor<Msg: Message> {
+  router_config: RouterConfig,
+  routee_props: Rc<dyn Props<Msg>>,
+  routees: Vec<ActorRef<Msg>>,
+  next_index: usize,
+}
+
+impl<Msg: Message> RouterActor<Msg> {
+  /// Returns the routees the next message goes to.
+  fn select(&mut self) -> Vec<ActorRef<Msg>> {
+    if self.routees.is_empty() {
+      return Vec::new();
+    }
+    match self.router_config {
+      RouterConfig::RoundRobinPool { .. } => {
+        let index = self.next_index % self.routees.len();
+        self.next_index = self.next_index.wrapping_add(1);
+        vec![self.routees[index].clone()]
+      }
+      RouterConfig::RandomPool { .. } => {
+        let index = thread_rng().gen_range(0..self.routees.len());
+        vec![self.routees[index].clone()]
+      }
+      RouterConfig::BroadcastPool { .. } => self.routees.clone(),
+    }
+  }
+}
+
+impl<Msg: Message> ActorBehavior<Msg> for RouterActor<Msg> {
+  fn pre_start(&mut self, mut ctx: ActorContext<Msg>) -> ActorResult<()> {
+    self.routees = (1..=self.router_config.nr_of_instances())
+      .map(|n| ctx.spawn(self.routee_props.clone(), &format!("routee-{}", n)))
+      .collect();
+    log::debug!("pre_start: {} routees of {:?}", self.routees.len(), self.router_config);
+    Ok(())
+  }
+
+  fn receive(&mut self, mut ctx: ActorContext<Msg>, msg: Msg) -> ActorResult<()> {
+    let routees = self.select();
+    if routees.is_empty() {
+      log::warn!("receive: no routees to route to");
+      return Ok(());
+    }
+    for routee_ref in &routees {
+      ctx.forward(routee_ref, msg.clone());
+    }
+    Ok(())
+  }
+
+  fn child_terminated(&mut self, child: ActorRef<AnyMessage>) -> ActorResult<()> {
+    let child_path = child.path();
+    self.routees.retain(|routee_ref| routee_ref.path() != child_path);
+    log::debug!("child_terminated: {} left {} routees", child_path, self.routees.len());
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::core::actor::actor_cell::ActorCellBehavior;
+  use crate::core::actor::actor_path::{ActorPath, ActorPathBehavior};
+  use crate::core::actor::actor_system::actor_system_builder::ActorSystemBuilder;
+  use crate::core::actor::actor_system::actor_system_settings::ActorSystemSettings;
+  use crate::core::actor::props::{FunctionProps, PropsSettingsBehavior};
+  use crate::core::actor::test_kit::{init_logger, start_system, EventProbe, ReportingActor};
+  use std::time::Duration;
+
+  #[derive(Debug, Clone)]
+  struct Routee;
+
+  impl ActorBehavior<String> for Routee {
+    fn receive(&mut self, _ctx: ActorContext<String>, _msg: String) -> ActorResult<()> {
+      Ok(())
+    }
+  }
+
+  fn router_of(router_config: RouterConfig) -> RouterActor<String> {
+    let routees = (1..=router_config.nr_of_instances())
+      .map(|n| ActorRef::of_mock(ActorPath::from_string(&format!("test://test/user/router/routee-{}", n))))
+      .collect();
+    RouterActor {
+      router_config,
+      routee_props: Rc::new(FunctionProps::of_actor(|| Routee)),
+      routees,
+      next_index: 0,
+    }
+  }
+
+  fn names(routees: Vec<ActorRef<String>>) -> Vec<String> {
+    routees
+      .into_iter()
+      .map(|routee_ref| routee_ref.path().name().to_string())
+      .collect()
+  }
+
+  #[test]
+  fn test_round_robin_pool_selects_routees_in_turn() {
+    let mut router = router_of(RouterConfig::of_round_robin_pool(3));
+    let selected = (0..4).flat_map(|_| names(router.select())).collect::<Vec<_>>();
+    assert_eq!(selected, vec!["routee-1", "routee-2", "routee-3", "routee-1"]);
+  }
+
+  #[test]
+  fn test_random_pool_selects_one_routee_each_time() {
+    let mut router = router_of(RouterConfig::of_random_pool(3));
+    let mut selected = (0..100)
+      .map(|_| {
+        let names = names(router.select());
+        assert_eq!(names.len(), 1);
+        names[0].clone()
+      })
+      .collect::<Vec<_>>();
+    selected.sort();
+    selected.dedup();
+    assert_eq!(selected, vec!["routee-1", "routee-2", "routee-3"]);
+  }
+
+  #[test]
+  fn test_broadcast_pool_selects_every_routee() {
+    let mut router = router_of(RouterConfig::of_broadcast_pool(3));
+    assert_eq!(names(router.select()), vec!["routee-1", "routee-2", "routee-3"]);
+    assert_eq!(names(router.select()), vec!["routee-1", "routee-2", "routee-3"]);
+  }
+
+  #[test]
+  fn test_terminated_routees_are_no_longer_selected() {
+    let mut router = router_of(RouterConfig::of_round_robin_pool(3));
+    let routee_2 = router.routees[1].clone().to_any(false);
+    router.child_terminated(routee_2).unwrap();
+    let selected = (0..3).flat_map(|_| names(router.select())).collect::<Vec<_>>();
+    assert_eq!(selected, vec!["routee-1", "routee-3", "routee-1"]);
+
+    for routee_ref in router.routees.clone() {
+      router.child_terminated(routee_ref.to_any(false)).unwrap();
+    }
+    assert!(router.select().is_empty());
+  }
+
+  #[test]
+  fn test_router_stops_routing_to_a_stopped_routee() {
+    let probe = EventProbe::new();
+    let (actor_system, _) = start_system(Rc::new(FunctionProps::of_actor(|| Routee)));
+    let routee_props = FunctionProps::of_actor_with_args(probe.events_tx(), |events_tx| {
+      ReportingActor::new(events_tx.clone())
+    })
+    .with_router(RouterConfig::of_round_robin_pool(2));
+    let mut router_ref = actor_system.actor_of(Rc::new(routee_props), "router");
+
+    router_ref.tell("first".to_string());
+    assert_eq!(probe.next(), "routee-1:first");
+
+    let children = || router_ref.actor_cell().unwrap().children();
+    children().get_child_ref("routee-1").unwrap().tell_poison_pill();
+    assert_eq!(probe.next(), "routee-1:post_stop");
+    while children().get_child_ref("routee-1").is_some() {
+      std::thread::sleep(Duration::from_millis(10));
+    }
+    router_ref.tell("second".to_string());
+    router_ref.tell("third".to_string());
+    assert_eq!(probe.next(), "routee-2:second");
+    assert_eq!(probe.next(), "routee-2:third");
+  }
+
+  #[test]
+  fn test_routees_keep_the_deployment_of_the_router_but_never_become_routers() {
+    init_logger();
+    let probe = EventProbe::new();
+    let settings = ActorSystemSettings {
+      deployments: vec![
+        Deploy::new("/user/pool")
+          .with_router_config(RouterConfig::of_round_robin_pool(2))
+          .with_mailbox_type(MailboxType::of_unbounded_deque()),
+        Deploy::new("/user/*/*").with_router_config(RouterConfig::of_broadcast_pool(3)),
+      ],
+      ..ActorSystemSettings::default()
+    };
+    let actor_system = ActorSystemBuilder::new("test")
+      .with_guardian_props(Rc::new(FunctionProps::of_actor(|| Routee)))
+      .with_settings(settings)
+      .build()
+      .unwrap();
+    let mut pool_ref = actor_system.actor_of(ReportingActor::props(probe.events_tx()), "pool");
+
+    pool_ref.tell("job-1".to_string());
+    pool_ref.tell("job-2".to_string());
+    assert_eq!(probe.next_n_sorted(2), vec!["routee-1:job-1", "routee-2:job-2"]);
+    probe.expect_no_event(Duration::from_millis(200));
+
+    let routee_ref = pool_ref.actor_cell().unwrap().children().get_child_ref("routee-1").unwrap();
+    let routee_cell = routee_ref.actor_cell().unwrap();
+    assert!(routee_cell.children().children().is_empty());
+    assert_eq!(routee_cell.mailbox().mailbox_type(), MailboxType::of_unbounded_deque());
+  }
+}
@@ -52,6 +52,8 @@ pub enum QueueError<E> {
   PoolError,
   #[error("Failed to peek an element")]
   PeekError,
+  #[error("Unsupported operation: {0}")]
+  UnsupportedOperationError(&'static str),
 }
 
 #[derive(Debug, Clone)]
@@ -155,6 +157,13 @@ pub trait QueueWriterBehavior<E: Element>: QueueBehavior<E> {
   fn offer(&mut self, e: E) -> Result<()>;
 }
 
+pub trait DequeWriterBehavior<E: Element>: QueueWriterBehavior<E> {
+  /// Inserts the specified element at the head of this queue,
+  /// if it can be done immediately without violating the capacity limit.<br/>
+  /// 容量制限に違反せずにすぐ実行できる場合は、指定された要素をこのキューの先頭に挿入します。
+  fn offer_first(&mut self, e: E) -> Result<()>;
+}
+
 pub trait QueueReaderBehavior<E: Element>: QueueBehavior<E> {
   /// Retrieves and deletes the head of the queue. Returns None if the queue is empty.<br/>
   /// キューの先頭を取得および削除します。キューが空の場合は None を返します。
@@ -306,6 +315,17 @@ impl<E: Element + 'static> QueueWriterBehavior<E> for QueueWriter<E> {
   }
 }
 
+impl<E: Element + 'static> DequeWriterBehavior<E> for QueueWriter<E> {
+  fn offer_first(&mut self, e: E) -> Result<()> {
+    match self {
+      QueueWriter::Vec(q) => q.offer_first(e),
+      QueueWriter::MPSC(_) => Err(anyhow::Error::new(QueueError::<E>::UnsupportedOperationError(
+        "offer_first on an MPSC queue",
+      ))),
+    }
+  }
+}
+
 impl<E: Element + 'static> QueueBehavior<E> for QueueReader<E> {
   fn len(&self) -> QueueSize {
     match self {
@@ -1,6 +1,6 @@
 use crate::infrastructure::queue::{
-  Element, QueueBehavior, QueueError, QueueRWFactoryBehavior, QueueReaderBehavior, QueueReaderFactoryBehavior,
-  QueueSize, QueueWithRWFactoryBehavior, QueueWriterBehavior, QueueWriterFactoryBehavior,
+  DequeWriterBehavior, Element, QueueBehavior, QueueError, QueueRWFactoryBehavior, QueueReaderBehavior,
+  QueueReaderFactoryBehavior, QueueSize, QueueWithRWFactoryBehavior, QueueWriterBehavior, QueueWriterFactoryBehavior,
 };
 use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
@@ -117,6 +117,18 @@ impl<E: Element + 'static> QueueWriterBehavior<E> for QueueVecWriter<E> {
   }
 }
 
+impl<E: Element + 'static> DequeWriterBehavior<E> for QueueVecWriter<E> {
+  fn offer_first(&mut self, e: E) -> anyhow::Result<()> {
+    if self.non_full() {
+      let mut mg = self.queue.values.lock().unwrap();
+      mg.push_front(e);
+      Ok(())
+    } else {
+      Err(anyhow::Error::new(QueueError::OfferError(e)))
+    }
+  }
+}
+
 impl<E: Element + 'static> QueueBehavior<E> for QueueVecReader<E> {
   fn len(&self) -> QueueSize {
     self.queue.len()
@@ -195,4 +207,18 @@ mod tests {
     assert_eq!(reader.poll().unwrap().unwrap(), 1);
     assert_eq!(reader.len(), QueueSize::Limited(1));
   }
+
+  #[test]
+  fn test_queue_vec_offer_first() {
+    init_logger();
+    let queue = QueueVec::<i32>::new();
+
+    let mut writer = queue.writer();
+    writer.offer(2).unwrap();
+    writer.offer_first(1).unwrap();
+
+    let mut reader = queue.reader();
+    assert_eq!(reader.poll().unwrap().unwrap(), 1);
+    assert_eq!(reader.poll().unwrap().unwrap(), 2);
+  }
 }